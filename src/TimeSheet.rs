@@ -1,19 +1,88 @@
-use chrono::{Date, DateTime, Duration, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Utc, Weekday};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
-#[derive(serde::Deserialize, serde::Serialize, Debug)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+#[serde(from = "StoredTimeSheetEntry")]
 pub struct TimeSheetEntry {
     pub project_type: String,
     pub work_start_datetime: DateTime<Utc>,
     pub work_end_datetime: DateTime<Utc>,
     pub notes: String,
+    pub created_at: DateTime<Utc>,
+    pub modified_at: DateTime<Utc>,
+    /// Work measured in something other than time, like tickets closed
+    pub units: Option<f64>,
+    /// Finer grained than the project, like "bugfix" or "review"
+    pub tags: Vec<String>,
+    /// Only billable time is charged at the project's rate
+    pub billable: bool,
+}
+
+// Entries saved by older versions are missing some fields; this is what we actually
+// deserialize so those can be filled in from the rest of the entry.
+#[derive(serde::Deserialize)]
+struct StoredTimeSheetEntry {
+    project_type: String,
+    work_start_datetime: DateTime<Utc>,
+    work_end_datetime: DateTime<Utc>,
+    notes: String,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    modified_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    units: Option<f64>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default = "default_billable")]
+    billable: bool,
+}
+
+// Entries from before the billable flag were all billed
+fn default_billable() -> bool {
+    true
+}
+
+// These fields are saved as plain values, so formats like ron that spell out Some(..)
+// need telling that a value being there is the Some case
+fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+impl From<StoredTimeSheetEntry> for TimeSheetEntry {
+    fn from(stored: StoredTimeSheetEntry) -> Self {
+        let created_at = stored.created_at.unwrap_or(stored.work_start_datetime);
+        TimeSheetEntry {
+            project_type: stored.project_type,
+            work_start_datetime: stored.work_start_datetime,
+            work_end_datetime: stored.work_end_datetime,
+            notes: stored.notes,
+            created_at,
+            modified_at: stored.modified_at.unwrap_or(created_at),
+            units: stored.units,
+            tags: stored.tags,
+            billable: stored.billable,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct TimeSheetSummary {
     pub summary: HashMap<NaiveDate, TimesheetDaySummary>,
     pub projects: Vec<String>,
+    /// Dates that have entries
     pub dates: Vec<NaiveDate>,
+    /// Every date in the summarized period, including ones without entries
+    pub period_dates: Vec<NaiveDate>,
+    /// Total units recorded per project, only projects with units are included
+    pub project_units: HashMap<String, f64>,
+    /// Projects shown in the summary but left out of the day, week, period and cost totals
+    pub excluded_from_totals: HashSet<String>,
 }
 
 #[derive(Debug)]
@@ -24,31 +93,390 @@ pub struct TimesheetDaySummary {
 #[derive(Debug)]
 pub struct ProjectDaySummary {
     pub hours_worked: Duration,
+    /// The part of hours_worked from billable entries
+    pub billable_hours: Duration,
     pub notes: String,
 }
 
-impl TimeSheetEntry {
-    pub fn from_minutes(
-        project_type: &String,
-        minutes: f32,
-        notes: &String,
-        today_date: &Date<Utc>,
-    ) -> TimeSheetEntry {
-        let work_start_datetime = today_date.and_hms(0, 0, 0);
-        let mut work_end_datetime = work_start_datetime;
-        if minutes >= 0.0 {
-            debug_assert!(minutes < (60.0 * 24.0));
-            let minutes_int = minutes.floor() as u32;
-            let seconds_int = ((minutes - minutes.floor()) * 60.0).round() as u32;
-            work_end_datetime = today_date.and_hms(minutes_int / 60, minutes_int % 60, seconds_int);
+/// Hours and cost of the projects billed to one client over a summary period
+#[derive(Debug, PartialEq)]
+pub struct ClientSummary {
+    /// None for the projects without a client
+    pub client: Option<String>,
+    /// Project, rounded hours and cost when the project has a rate
+    pub projects: Vec<(String, Duration, Option<f64>)>,
+    pub hours: Duration,
+    pub cost: f64,
+}
+
+/// Sign-off details for a summary period, keyed by the period start date
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct PeriodSubmission {
+    pub period_end: NaiveDate,
+    pub approved_by: String,
+    pub submitted_on: DateTime<Utc>,
+    /// Entries inside a locked period can't be changed
+    pub locked: bool,
+}
+
+/// Returns true if `date` falls inside a locked, submitted period
+pub fn is_date_locked(submissions: &HashMap<NaiveDate, PeriodSubmission>, date: NaiveDate) -> bool {
+    submissions.iter().any(|(period_start, submission)| {
+        submission.locked && *period_start <= date && date <= submission.period_end
+    })
+}
+
+/// Returns true if `entry` starts on a day in `utc_offset` inside a locked period
+pub fn is_entry_locked(
+    submissions: &HashMap<NaiveDate, PeriodSubmission>,
+    entry: &TimeSheetEntry,
+    utc_offset: &FixedOffset,
+) -> bool {
+    is_date_locked(
+        submissions,
+        entry
+            .work_start_datetime
+            .with_timezone(utc_offset)
+            .date_naive(),
+    )
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingDirection {
+    Up,
+    Down,
+    Nearest,
+}
+
+impl RoundingDirection {
+    pub const ALL: [RoundingDirection; 3] = [
+        RoundingDirection::Up,
+        RoundingDirection::Down,
+        RoundingDirection::Nearest,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RoundingDirection::Up => "round up",
+            RoundingDirection::Down => "round down",
+            RoundingDirection::Nearest => "round to nearest",
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundingSettings {
+    /// Rounding increment in minutes, 0 turns rounding off
+    pub increment_minutes: i64,
+    pub direction: RoundingDirection,
+}
+
+impl Default for RoundingSettings {
+    fn default() -> Self {
+        RoundingSettings {
+            increment_minutes: 0,
+            direction: RoundingDirection::Up,
+        }
+    }
+}
+
+impl RoundingSettings {
+    pub fn apply(&self, duration: Duration) -> Duration {
+        round_duration(
+            duration,
+            Duration::minutes(self.increment_minutes),
+            self.direction,
+        )
+    }
+
+    /// `apply` for the length of a new entry. A span with any time in it that would round
+    /// down to nothing is rounded up instead, so recorded work is never lost.
+    pub fn apply_to_entry(&self, duration: Duration) -> Duration {
+        let rounded = self.apply(duration);
+        if rounded <= Duration::zero() && duration > Duration::zero() {
+            round_duration(
+                duration,
+                Duration::minutes(self.increment_minutes),
+                RoundingDirection::Up,
+            )
+        } else {
+            rounded
+        }
+    }
+}
+
+/// Rounds `duration` to a multiple of `increment`. A zero or negative increment
+/// leaves the duration untouched. Nearest rounds halfway values up.
+pub fn round_duration(
+    duration: Duration,
+    increment: Duration,
+    direction: RoundingDirection,
+) -> Duration {
+    let increment_seconds = increment.num_seconds();
+    if increment_seconds <= 0 {
+        return duration;
+    }
+    let seconds = duration.num_seconds();
+    let remainder = seconds.rem_euclid(increment_seconds);
+    let floor = seconds - remainder;
+    let rounded = match direction {
+        RoundingDirection::Down => floor,
+        RoundingDirection::Up if remainder == 0 => seconds,
+        RoundingDirection::Up => floor + increment_seconds,
+        RoundingDirection::Nearest if remainder * 2 >= increment_seconds => {
+            floor + increment_seconds
+        }
+        RoundingDirection::Nearest => floor,
+    };
+    Duration::seconds(rounded)
+}
+
+/// Tags typed as a comma separated list, blanks and repeats left out
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(',').map(|tag| tag.trim()) {
+        if !tag.is_empty()
+            && !tags
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(tag))
+        {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+pub const UNTAGGED: &str = "(untagged)";
+
+/// A copy of each entry for every tag it has, with the tag in place of the project, so a
+/// summary of them is grouped by tag. Entries with several tags count toward each one,
+/// and entries without any are grouped under UNTAGGED.
+pub fn entries_by_tag(entries: &[TimeSheetEntry]) -> Vec<TimeSheetEntry> {
+    let mut tagged_entries = Vec::new();
+    for entry in entries {
+        if entry.tags.is_empty() {
+            let mut tagged_entry = entry.clone();
+            tagged_entry.project_type = UNTAGGED.to_string();
+            tagged_entries.push(tagged_entry);
+        }
+        for tag in entry.tags.iter() {
+            let mut tagged_entry = entry.clone();
+            tagged_entry.project_type = tag.to_string();
+            tagged_entries.push(tagged_entry);
+        }
+    }
+    tagged_entries
+}
+
+/// Summary row for entries whose project type has been deleted
+pub const UNCATEGORIZED: &str = "(uncategorized)";
+
+/// Project names used by entries that aren't in `project_types`, sorted
+pub fn uncategorized_projects(entries: &[TimeSheetEntry], project_types: &[String]) -> Vec<String> {
+    let mut projects: Vec<String> = Vec::new();
+    for entry in entries {
+        if !project_types.contains(&entry.project_type) && !projects.contains(&entry.project_type) {
+            projects.push(entry.project_type.to_string());
+        }
+    }
+    projects.sort();
+    projects
+}
+
+/// A copy of the entries for summarizing, with the ones whose project type was deleted
+/// grouped under UNCATEGORIZED.
+pub fn entries_with_uncategorized(
+    entries: &[TimeSheetEntry],
+    project_types: &[String],
+) -> Vec<TimeSheetEntry> {
+    entries
+        .iter()
+        .map(|entry| {
+            let mut entry = entry.clone();
+            if !project_types.contains(&entry.project_type) {
+                entry.project_type = UNCATEGORIZED.to_string();
+            }
+            entry
+        })
+        .collect()
+}
+
+/// Moves every entry whose project type was deleted to `project_type`, leaving the ones in
+/// locked periods as they are. Returns how many entries changed and how many were left.
+pub fn reassign_uncategorized(
+    entries: &mut [TimeSheetEntry],
+    project_types: &[String],
+    project_type: &str,
+    submissions: &HashMap<NaiveDate, PeriodSubmission>,
+    utc_offset: &FixedOffset,
+) -> (usize, usize) {
+    let mut reassigned = 0;
+    let mut locked = 0;
+    for entry in entries.iter_mut() {
+        if !project_types.contains(&entry.project_type) {
+            if is_entry_locked(submissions, entry, utc_offset) {
+                locked += 1;
+            } else {
+                entry.project_type = project_type.to_string();
+                entry.touch();
+                reassigned += 1;
+            }
         }
+    }
+    (reassigned, locked)
+}
+
+/// Snaps a duration to the nearest quarter hour, used when timer entries are
+/// stored in quarter hour increments
+pub fn snap_to_quarter_hour(duration: Duration) -> Duration {
+    round_duration(duration, Duration::minutes(15), RoundingDirection::Nearest)
+}
 
+/// Hours as a decimal with two places, like 1.50, for the summary and its exports
+pub fn format_duration_hours(span: &Duration) -> String {
+    let mut total_hours: f64 = span.num_minutes() as f64;
+    total_hours /= 60.0;
+    format!("{0:.2}", total_hours)
+}
+
+impl TimeSheetEntry {
+    pub fn new(
+        project_type: &str,
+        work_start_datetime: DateTime<Utc>,
+        work_end_datetime: DateTime<Utc>,
+        notes: &str,
+    ) -> TimeSheetEntry {
+        let now = chrono::offset::Utc::now();
         TimeSheetEntry {
             project_type: project_type.to_owned(),
             work_start_datetime,
             work_end_datetime,
             notes: notes.to_owned(),
+            created_at: now,
+            modified_at: now,
+            units: None,
+            tags: Vec::new(),
+            billable: true,
+        }
+    }
+
+    /// Splits the entry at each midnight it crosses, returning the time worked
+    /// on each day it touches
+    pub fn split_at_midnight(&self) -> Vec<(NaiveDate, Duration)> {
+        self.split_at_midnight_in(&FixedOffset::east(0))
+    }
+
+    /// Like `split_at_midnight`, with the days and midnights taken at `utc_offset`
+    pub fn split_at_midnight_in(&self, utc_offset: &FixedOffset) -> Vec<(NaiveDate, Duration)> {
+        let mut allocation = Vec::new();
+        let work_end_datetime = self.work_end_datetime.with_timezone(utc_offset);
+        let mut segment_start = self.work_start_datetime.with_timezone(utc_offset);
+        loop {
+            let next_midnight = (segment_start.date() + Duration::days(1)).and_hms(0, 0, 0);
+            if work_end_datetime <= next_midnight {
+                allocation.push((
+                    segment_start.date_naive(),
+                    work_end_datetime - segment_start,
+                ));
+                return allocation;
+            }
+            allocation.push((segment_start.date_naive(), next_midnight - segment_start));
+            segment_start = next_midnight;
+        }
+    }
+
+    /// A new entry for the same work on `date`, starting at the same time of day at
+    /// `utc_offset` and lasting as long
+    pub fn duplicate_on(&self, date: NaiveDate, utc_offset: &FixedOffset) -> TimeSheetEntry {
+        let start_time = self.work_start_datetime.with_timezone(utc_offset).time();
+        let work_start_datetime = utc_offset
+            .from_local_datetime(&date.and_time(start_time))
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut entry = TimeSheetEntry::new(
+            &self.project_type,
+            work_start_datetime,
+            work_start_datetime + (self.work_end_datetime - self.work_start_datetime),
+            &self.notes,
+        );
+        entry.units = self.units;
+        entry.tags = self.tags.clone();
+        entry.billable = self.billable;
+        entry
+    }
+
+    /// True when the two entries share some time. Entries that only touch, one ending as
+    /// the other starts, don't overlap
+    pub fn overlaps(&self, other: &TimeSheetEntry) -> bool {
+        self.work_start_datetime < other.work_end_datetime
+            && other.work_start_datetime < self.work_end_datetime
+    }
+
+    /// True when both entries record the same work, ignoring when they were created or
+    /// modified
+    pub fn is_same_work(&self, other: &TimeSheetEntry) -> bool {
+        self.project_type == other.project_type
+            && self.work_start_datetime == other.work_start_datetime
+            && self.work_end_datetime == other.work_end_datetime
+            && self.notes == other.notes
+    }
+
+    /// Moves the end of the entry so its length is rounded by `rounding`
+    pub fn round_length(&mut self, rounding: &RoundingSettings) {
+        self.work_end_datetime = self.work_start_datetime
+            + rounding.apply_to_entry(self.work_end_datetime - self.work_start_datetime);
+    }
+
+    /// Splits the entry in two at `at`, which has to fall inside it, with the second part
+    /// going to `project_type`. The first part keeps the units since they can't be split.
+    pub fn split_at(
+        &self,
+        at: DateTime<Utc>,
+        project_type: &str,
+    ) -> Result<(TimeSheetEntry, TimeSheetEntry), String> {
+        if at <= self.work_start_datetime || at >= self.work_end_datetime {
+            return Err("the split time has to be between the entry's start and end".to_string());
         }
+        let mut first = self.clone();
+        first.work_end_datetime = at;
+        first.touch();
+        let mut second = TimeSheetEntry::new(project_type, at, self.work_end_datetime, &self.notes);
+        second.tags = self.tags.clone();
+        second.billable = self.billable;
+        Ok((first, second))
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags
+            .iter()
+            .any(|entry_tag| entry_tag.eq_ignore_ascii_case(tag))
+    }
+
+    /// Call whenever an existing entry is edited
+    pub fn touch(&mut self) {
+        self.modified_at = chrono::offset::Utc::now();
+    }
+
+    /// An entry starting at midnight on `date` at `utc_offset` and lasting `minutes`. A day
+    /// or more runs on into the following days, and negative minutes give an empty entry.
+    pub fn from_minutes(
+        project_type: &str,
+        minutes: f32,
+        notes: &str,
+        date: &NaiveDate,
+        utc_offset: &FixedOffset,
+    ) -> TimeSheetEntry {
+        let work_start_datetime = utc_offset
+            .from_local_datetime(&date.and_hms(0, 0, 0))
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut work_end_datetime = work_start_datetime;
+        if minutes >= 0.0 {
+            work_end_datetime =
+                work_start_datetime + Duration::seconds((minutes * 60.0).round() as i64);
+        }
+
+        TimeSheetEntry::new(project_type, work_start_datetime, work_end_datetime, notes)
     }
 }
 
@@ -57,54 +485,90 @@ impl TimeSheetSummary {
         entries: &[TimeSheetEntry],
         start_date: &NaiveDate,
         end_date: &NaiveDate,
+    ) -> TimeSheetSummary {
+        TimeSheetSummary::new_in(entries, start_date, end_date, &FixedOffset::east(0))
+    }
+
+    /// Summarizes the entries with days running midnight to midnight at `utc_offset`
+    pub fn new_in(
+        entries: &[TimeSheetEntry],
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+        utc_offset: &FixedOffset,
+    ) -> TimeSheetSummary {
+        TimeSheetSummary::from_entries_in(entries, start_date, end_date, utc_offset)
+    }
+
+    /// Like `new_in`, for entries already picked out some other way, such as by a filter,
+    /// without collecting them first
+    pub fn from_entries_in<'a>(
+        entries: impl IntoIterator<Item = &'a TimeSheetEntry>,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+        utc_offset: &FixedOffset,
     ) -> TimeSheetSummary {
         let mut summary: HashMap<NaiveDate, TimesheetDaySummary> = HashMap::new();
         let mut dates = HashSet::new();
-        let mut projects = HashSet::new();
-
-        for entry in entries.iter() {
-            let date_worked = entry.work_start_datetime.date_naive();
-            let project_worked = entry.project_type.to_string();
-            let duration_worked = entry.work_end_datetime - entry.work_start_datetime;
-            let project_notes = entry.notes.to_string();
-            if date_worked < *start_date || date_worked > *end_date {
-                continue;
-            }
-            dates.insert(date_worked);
-            projects.insert(project_worked.to_string());
+        let mut projects: HashSet<&str> = HashSet::new();
+        let mut project_units: HashMap<String, f64> = HashMap::new();
 
-            let timesheet_day_summary = match summary.get_mut(&date_worked) {
-                Some(day_summary) => day_summary,
-                None => {
-                    let ts_day_summary = TimesheetDaySummary {
-                        summary: HashMap::new(),
-                    };
-                    summary.insert(date_worked, ts_day_summary);
-                    summary.get_mut(&date_worked).unwrap()
+        for entry in entries {
+            let project_worked = entry.project_type.as_str();
+            // Notes and units are only attached to the first day of the entry inside the period
+            let mut remaining_notes = Some(entry.notes.as_str());
+            let mut remaining_units = entry.units;
+            for (date_worked, duration_worked) in entry.split_at_midnight_in(utc_offset) {
+                if date_worked < *start_date || date_worked > *end_date {
+                    continue;
                 }
-            };
+                let project_notes = remaining_notes.take().unwrap_or_default();
+                if let Some(units) = remaining_units.take() {
+                    *project_units
+                        .entry(project_worked.to_string())
+                        .or_insert(0.0) += units;
+                }
+                dates.insert(date_worked);
+                projects.insert(project_worked);
 
-            let project_day_summary = match timesheet_day_summary.summary.get_mut(&project_worked) {
-                Some(project_summary) => project_summary,
-                None => {
-                    let p_day_summary = ProjectDaySummary {
-                        hours_worked: Duration::zero(),
-                        notes: String::new(),
+                let timesheet_day_summary =
+                    summary
+                        .entry(date_worked)
+                        .or_insert_with(|| TimesheetDaySummary {
+                            summary: HashMap::new(),
+                        });
+
+                let project_day_summary =
+                    match timesheet_day_summary.summary.get_mut(project_worked) {
+                        Some(project_summary) => project_summary,
+                        None => {
+                            let p_day_summary = ProjectDaySummary {
+                                hours_worked: Duration::zero(),
+                                billable_hours: Duration::zero(),
+                                notes: String::new(),
+                            };
+                            timesheet_day_summary
+                                .summary
+                                .insert(project_worked.to_string(), p_day_summary);
+                            timesheet_day_summary
+                                .summary
+                                .get_mut(project_worked)
+                                .unwrap()
+                        }
                     };
-                    timesheet_day_summary
-                        .summary
-                        .insert(project_worked.to_string(), p_day_summary);
-                    timesheet_day_summary
-                        .summary
-                        .get_mut(&project_worked)
-                        .unwrap()
-                }
-            };
 
-            project_day_summary.hours_worked = project_day_summary.hours_worked + duration_worked;
-            if !project_notes.is_empty() {
-                project_day_summary.notes =
-                    format!("{} \n {}", project_day_summary.notes, project_notes);
+                project_day_summary.hours_worked =
+                    project_day_summary.hours_worked + duration_worked;
+                if entry.billable {
+                    project_day_summary.billable_hours =
+                        project_day_summary.billable_hours + duration_worked;
+                }
+                if !project_notes.is_empty() {
+                    // One entry's notes per line
+                    if !project_day_summary.notes.is_empty() {
+                        project_day_summary.notes.push('\n');
+                    }
+                    project_day_summary.notes.push_str(project_notes);
+                }
             }
         }
         let mut final_dates: Vec<NaiveDate> = dates.into_iter().collect();
@@ -113,67 +577,2245 @@ impl TimeSheetSummary {
         TimeSheetSummary {
             summary,
             dates: final_dates,
-            projects: projects.into_iter().collect(),
+            period_dates: date_range(start_date, end_date),
+            project_units,
+            projects: projects.into_iter().map(str::to_string).collect(),
+            excluded_from_totals: HashSet::new(),
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    pub fn hours_worked(&self, date: &NaiveDate, project: &str) -> Duration {
+        self.summary
+            .get(date)
+            .and_then(|day| day.summary.get(project))
+            .map_or(Duration::zero(), |project_summary| {
+                project_summary.hours_worked
+            })
+    }
 
-    #[test]
-    fn test_time_sheet_summary_empty_vec() {
-        let start_date = NaiveDate::from_ymd(2022, 07, 12);
-        let end_date = NaiveDate::from_ymd(2022, 07, 13);
-        let empty_vec: Vec<TimeSheetEntry> = Vec::new();
-        let time_sheet_summary = TimeSheetSummary::new(&empty_vec, &start_date, &end_date);
-        assert_eq!(time_sheet_summary.summary.len(), 0);
-        assert_eq!(time_sheet_summary.dates.len(), 0);
-        assert_eq!(time_sheet_summary.projects.len(), 0);
+    pub fn counts_in_totals(&self, project: &str) -> bool {
+        !self.excluded_from_totals.contains(project)
     }
 
-    #[test]
-    fn test_time_sheet_summary_one_item_vec() {
-        let start_date = NaiveDate::from_ymd(2022, 07, 12);
-        let end_date = NaiveDate::from_ymd(2022, 07, 13);
-        let mut entries: Vec<TimeSheetEntry> = Vec::new();
-        entries.push(TimeSheetEntry {
-            project_type: "test".to_string(),
-            work_start_datetime: datetime_from_ymd_hms(2022, 07, 12, 2, 0, 0),
-            work_end_datetime: datetime_from_ymd_hms(2022, 07, 12, 4, 0, 0),
-            notes: String::new(),
-        });
-        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
-        assert_eq!(time_sheet_summary.summary.len(), 1);
-        assert_eq!(time_sheet_summary.dates.len(), 1);
-        assert_eq!(time_sheet_summary.projects.len(), 1);
+    pub fn day_total(&self, date: &NaiveDate) -> Duration {
+        match self.summary.get(date) {
+            Some(day) => day
+                .summary
+                .iter()
+                .filter(|(project, _)| self.counts_in_totals(project))
+                .fold(Duration::zero(), |total, (_, project)| {
+                    total + project.hours_worked
+                }),
+            None => Duration::zero(),
+        }
+    }
 
-        assert_eq!(
-            time_sheet_summary
+    /// Running total for each date of `period_dates`, restarting on every `week_start`.
+    /// Each project's day is rounded first, the same as the summary grid.
+    pub fn week_to_date_totals(
+        &self,
+        week_start: Weekday,
+        rounding: &RoundingSettings,
+    ) -> Vec<Duration> {
+        let mut running_total = Duration::zero();
+        self.period_dates
+            .iter()
+            .map(|date| {
+                if date.weekday() == week_start {
+                    running_total = Duration::zero();
+                }
+                if let Some(day) = self.summary.get(date) {
+                    for (project, project_summary) in day.summary.iter() {
+                        if self.counts_in_totals(project) {
+                            running_total =
+                                running_total + rounding.apply(project_summary.hours_worked);
+                        }
+                    }
+                }
+                running_total
+            })
+            .collect()
+    }
+
+    /// Average time worked on the working days of the period. Time on other days
+    /// isn't counted.
+    pub fn average_per_working_day(&self, working_days: &[Weekday]) -> Duration {
+        let days = working_days_in(&self.period_dates, working_days);
+        if days.is_empty() {
+            return Duration::zero();
+        }
+        let total = days
+            .iter()
+            .fold(Duration::zero(), |total, date| total + self.day_total(date));
+        total / days.len() as i32
+    }
+
+    /// Working days in the period that have nothing logged
+    pub fn missing_working_days(&self, working_days: &[Weekday]) -> Vec<NaiveDate> {
+        working_days_in(&self.period_dates, working_days)
+            .into_iter()
+            .filter(|date| !self.summary.contains_key(date))
+            .collect()
+    }
+
+    /// Time worked per project on `date`, largest first
+    pub fn day_project_totals(&self, date: &NaiveDate) -> Vec<(String, Duration)> {
+        let mut totals: Vec<(String, Duration)> = match self.summary.get(date) {
+            Some(day) => day
                 .summary
-                .get(&NaiveDate::from_ymd(2022, 07, 12))
-                .unwrap()
+                .iter()
+                .map(|(project, project_summary)| {
+                    (project.to_string(), project_summary.hours_worked)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        totals
+    }
+
+    /// Time worked on `project` over the period, rounding each day like the summary grid
+    pub fn project_total(&self, project: &str, rounding: &RoundingSettings) -> Duration {
+        self.summary
+            .values()
+            .filter_map(|day| day.summary.get(project))
+            .fold(Duration::zero(), |total, project_summary| {
+                total + rounding.apply(project_summary.hours_worked)
+            })
+    }
+
+    /// Billable time on `project` over the period, rounding each day like the summary grid
+    pub fn project_billable_total(&self, project: &str, rounding: &RoundingSettings) -> Duration {
+        self.summary
+            .values()
+            .filter_map(|day| day.summary.get(project))
+            .fold(Duration::zero(), |total, project_summary| {
+                total + rounding.apply(project_summary.billable_hours)
+            })
+    }
+
+    /// Billable time on `date` over the projects that count in totals, rounded per project
+    pub fn billable_day_total(&self, date: &NaiveDate, rounding: &RoundingSettings) -> Duration {
+        match self.summary.get(date) {
+            Some(day) => day
                 .summary
-                .get(&"test".to_string())
-                .unwrap()
-                .hours_worked
-                .num_hours(),
-            2
-        )
+                .iter()
+                .filter(|(project, _)| self.counts_in_totals(project))
+                .fold(Duration::zero(), |total, (_, project_summary)| {
+                    total + rounding.apply(project_summary.billable_hours)
+                }),
+            None => Duration::zero(),
+        }
     }
 
-    fn datetime_from_ymd_hms(
-        year: i32,
-        month: u32,
-        day: u32,
-        hour: u32,
-        minute: u32,
-        second: u32,
-    ) -> DateTime<Utc> {
-        NaiveDate::from_ymd(year, month, day)
-            .and_hms(hour, minute, second)
-            .and_local_timezone(Utc)
-            .unwrap()
+    /// Amount for the billable time on `project` at its hourly rate, using the rounded
+    /// hours of each day so it matches the summary grid. None when the project has no rate.
+    pub fn project_cost(
+        &self,
+        project: &str,
+        hourly_rates: &HashMap<String, f64>,
+        rounding: &RoundingSettings,
+    ) -> Option<f64> {
+        let hourly_rate = hourly_rates.get(project)?;
+        let hours = self.project_billable_total(project, rounding);
+        Some(hours.num_minutes() as f64 / 60.0 * hourly_rate)
+    }
+
+    /// Sum of `project_cost` over the projects that have a rate and count in totals
+    pub fn total_cost(
+        &self,
+        hourly_rates: &HashMap<String, f64>,
+        rounding: &RoundingSettings,
+    ) -> f64 {
+        self.projects
+            .iter()
+            .filter(|project| self.counts_in_totals(project))
+            .filter_map(|project| self.project_cost(project, hourly_rates, rounding))
+            .sum()
+    }
+
+    /// Projects that count in totals grouped under their client, sorted by client name
+    /// with the projects without a client last
+    pub fn client_totals(
+        &self,
+        project_clients: &HashMap<String, String>,
+        hourly_rates: &HashMap<String, f64>,
+        rounding: &RoundingSettings,
+    ) -> Vec<ClientSummary> {
+        let mut clients: Vec<ClientSummary> = Vec::new();
+        for project in self.projects.iter() {
+            if !self.counts_in_totals(project) {
+                continue;
+            }
+            let client = project_clients.get(project).cloned();
+            let hours = self.project_total(project, rounding);
+            let cost = self.project_cost(project, hourly_rates, rounding);
+            let index = match clients.iter().position(|summary| summary.client == client) {
+                Some(index) => index,
+                None => {
+                    clients.push(ClientSummary {
+                        client,
+                        projects: Vec::new(),
+                        hours: Duration::zero(),
+                        cost: 0.0,
+                    });
+                    clients.len() - 1
+                }
+            };
+            let summary = &mut clients[index];
+            summary.projects.push((project.to_string(), hours, cost));
+            summary.hours = summary.hours + hours;
+            summary.cost += cost.unwrap_or(0.0);
+        }
+        for summary in clients.iter_mut() {
+            summary.projects.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+        // None sorts first, so compare the presence of a client before the name
+        clients.sort_by(|a, b| {
+            a.client
+                .is_none()
+                .cmp(&b.client.is_none())
+                .then_with(|| a.client.cmp(&b.client))
+        });
+        clients
+    }
+
+    pub fn total_hours_worked(&self) -> Duration {
+        self.summary
+            .values()
+            .flat_map(|day| day.summary.iter())
+            .filter(|(project, _)| self.counts_in_totals(project))
+            .fold(Duration::zero(), |total, (_, project)| {
+                total + project.hours_worked
+            })
+    }
+
+    /// Unrounded time worked on each project over the period
+    pub fn total_hours_per_project(&self) -> HashMap<String, Duration> {
+        let mut totals: HashMap<String, Duration> = HashMap::new();
+        for day in self.summary.values() {
+            for (project, project_summary) in day.summary.iter() {
+                let total = totals
+                    .entry(project.to_string())
+                    .or_insert_with(Duration::zero);
+                *total = *total + project_summary.hours_worked;
+            }
+        }
+        totals
+    }
+
+    /// Unrounded time worked on every project that counts in totals over the period
+    pub fn grand_total(&self) -> Duration {
+        self.total_hours_worked()
+    }
+
+    /// Summarizes the entries in a file saved with "Export all entries", for building
+    /// reports without the app
+    pub fn from_file(
+        path: impl AsRef<Path>,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+    ) -> Result<TimeSheetSummary, String> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| format!("Couldn't read {}: {}", path.display(), error))?;
+        let entries: Vec<TimeSheetEntry> = ron::from_str(&contents)
+            .map_err(|error| format!("Couldn't read the entries: {}", error))?;
+        Ok(TimeSheetSummary::new(&entries, start_date, end_date))
+    }
+}
+
+/// Time worked on each day of the week, starting from `week_start`. Entries crossing
+/// midnight at `utc_offset` count toward each day they touch.
+pub fn weekday_totals<'a>(
+    entries: impl IntoIterator<Item = &'a TimeSheetEntry>,
+    week_start: Weekday,
+    utc_offset: &FixedOffset,
+) -> Vec<(Weekday, Duration)> {
+    let mut totals = [Duration::zero(); 7];
+    for entry in entries {
+        for (date, duration) in entry.split_at_midnight_in(utc_offset) {
+            let index = date.weekday().num_days_from_monday() as usize;
+            totals[index] = totals[index] + duration;
+        }
+    }
+    let mut weekday = week_start;
+    (0..7)
+        .map(|_| {
+            let total = (weekday, totals[weekday.num_days_from_monday() as usize]);
+            weekday = weekday.succ();
+            total
+        })
+        .collect()
+}
+
+/// Copies of the entries starting on `from`, moved to the same times of day on `to`, in
+/// start order. Days are taken at `utc_offset`.
+pub fn copy_day(
+    entries: &[TimeSheetEntry],
+    from: NaiveDate,
+    to: NaiveDate,
+    utc_offset: &FixedOffset,
+) -> Vec<TimeSheetEntry> {
+    let mut day_entries: Vec<&TimeSheetEntry> = entries
+        .iter()
+        .filter(|entry| {
+            entry
+                .work_start_datetime
+                .with_timezone(utc_offset)
+                .date_naive()
+                == from
+        })
+        .collect();
+    day_entries.sort_by_key(|entry| entry.work_start_datetime);
+    day_entries
+        .into_iter()
+        .map(|entry| entry.duplicate_on(to, utc_offset))
+        .collect()
+}
+
+/// The notes of the most recently created entries, without repeats or empty notes, newest
+/// first and at most `limit` of them
+pub fn recent_notes(entries: &[TimeSheetEntry], limit: usize) -> Vec<String> {
+    let mut newest_first: Vec<&TimeSheetEntry> = entries.iter().collect();
+    newest_first.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+    let mut notes: Vec<String> = Vec::new();
+    for entry in newest_first {
+        let entry_notes = entry.notes.trim();
+        if !entry_notes.is_empty() && !notes.iter().any(|seen| seen == entry_notes) {
+            notes.push(entry_notes.to_string());
+            if notes.len() == limit {
+                break;
+            }
+        }
+    }
+    notes
+}
+
+/// Indices of the entries that overlap another entry of the same project. Different
+/// projects can run at the same time, like concurrent timers.
+pub fn overlapping_entries(entries: &[TimeSheetEntry]) -> HashSet<usize> {
+    let mut by_project: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        by_project
+            .entry(entry.project_type.as_str())
+            .or_default()
+            .push(index);
+    }
+    let mut overlapping = HashSet::new();
+    for mut indices in by_project.into_values() {
+        indices.sort_by_key(|index| entries[*index].work_start_datetime);
+        // The entry reaching furthest so far, anything starting before its end overlaps it
+        let mut furthest: Option<usize> = None;
+        for index in indices {
+            let entry = &entries[index];
+            match furthest {
+                Some(furthest_index) if entries[furthest_index].overlaps(entry) => {
+                    overlapping.insert(furthest_index);
+                    overlapping.insert(index);
+                    if entry.work_end_datetime > entries[furthest_index].work_end_datetime {
+                        furthest = Some(index);
+                    }
+                }
+                Some(furthest_index)
+                    if entry.work_end_datetime <= entries[furthest_index].work_end_datetime => {}
+                _ => furthest = Some(index),
+            }
+        }
+    }
+    overlapping
+}
+
+/// Time recorded per project over every entry, most time first
+pub fn project_totals(entries: &[TimeSheetEntry]) -> Vec<(String, Duration)> {
+    let mut totals: HashMap<&str, Duration> = HashMap::new();
+    for entry in entries.iter() {
+        let total = totals
+            .entry(&entry.project_type)
+            .or_insert_with(Duration::zero);
+        *total = *total + (entry.work_end_datetime - entry.work_start_datetime);
+    }
+    let mut totals: Vec<(String, Duration)> = totals
+        .into_iter()
+        .map(|(project, total)| (project.to_string(), total))
+        .collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+/// Adds `name`, trimmed, to the project types. Empty names and ones already there in any
+/// case are refused, and the trimmed name is returned when it's added.
+pub fn add_project_type(project_types: &mut Vec<String>, name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Enter a name for the project type".to_string());
+    }
+    if let Some(existing) = project_types
+        .iter()
+        .find(|project_type| project_type.eq_ignore_ascii_case(name))
+    {
+        return Err(format!("{} is already a project type", existing));
+    }
+    project_types.push(name.to_string());
+    Ok(name.to_string())
+}
+
+/// The project type other than `old_name` that `new_name` matches ignoring case, like
+/// add_project_type compares them. Renaming to `new_name` merges into it.
+pub fn merge_target<'a>(
+    project_types: &'a [String],
+    old_name: &str,
+    new_name: &str,
+) -> Option<&'a String> {
+    project_types.iter().find(|project_type| {
+        *project_type != old_name && project_type.eq_ignore_ascii_case(new_name)
+    })
+}
+
+/// Renames a project type and every entry recorded against it. If `new_name` is
+/// already a project type, ignoring case, the two are merged into its spelling. Returns
+/// how many entries changed.
+pub fn rename_project(
+    project_types: &mut Vec<String>,
+    entries: &mut [TimeSheetEntry],
+    old_name: &str,
+    new_name: &str,
+) -> usize {
+    let merge_into = merge_target(project_types, old_name, new_name).cloned();
+    let new_name = merge_into.as_deref().unwrap_or(new_name);
+    if merge_into.is_some() {
+        project_types.retain(|project_type| project_type != old_name);
+    } else {
+        for project_type in project_types.iter_mut() {
+            if project_type == old_name {
+                *project_type = new_name.to_string();
+            }
+        }
+    }
+    let mut renamed = 0;
+    for entry in entries.iter_mut() {
+        if entry.project_type == old_name {
+            entry.project_type = new_name.to_string();
+            entry.touch();
+            renamed += 1;
+        }
+    }
+    renamed
+}
+
+/// Adds the imported entries that aren't already in `entries`. Returns how many were
+/// added and how many were skipped as duplicates.
+pub fn merge_entries(
+    entries: &mut Vec<TimeSheetEntry>,
+    imported: Vec<TimeSheetEntry>,
+) -> (usize, usize) {
+    let mut added = 0;
+    let mut skipped = 0;
+    for entry in imported {
+        if entries.iter().any(|existing| existing.is_same_work(&entry)) {
+            skipped += 1;
+        } else {
+            entries.push(entry);
+            added += 1;
+        }
+    }
+    (added, skipped)
+}
+
+/// The imported entries outside locked periods, and how many were left out for being in
+/// one, so an import can't add work to a period that's been signed off
+pub fn without_locked_entries(
+    imported: Vec<TimeSheetEntry>,
+    submissions: &HashMap<NaiveDate, PeriodSubmission>,
+    utc_offset: &FixedOffset,
+) -> (Vec<TimeSheetEntry>, usize) {
+    let (locked, unlocked): (Vec<TimeSheetEntry>, Vec<TimeSheetEntry>) = imported
+        .into_iter()
+        .partition(|entry| is_entry_locked(submissions, entry, utc_offset));
+    (unlocked, locked.len())
+}
+
+/// Parses a minutes value typed by the user, either plain decimal minutes ("90",
+/// "7.5") or hours and minutes ("1:30"). Anything else, including scientific
+/// notation, NaN and infinity, is rejected.
+pub fn parse_minutes(input: &str) -> Result<f32, String> {
+    let input = input.trim();
+    let is_digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if let Some((hours, minutes)) = input.split_once(':') {
+        let invalid = || format!("\"{}\" is not a valid h:mm time", input);
+        if !is_digits(hours) || !is_digits(minutes) || minutes.len() > 2 {
+            return Err(invalid());
+        }
+        let hours: f32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: f32 = minutes.parse().map_err(|_| invalid())?;
+        if minutes >= 60.0 {
+            return Err(format!("\"{}\" has more than 59 minutes", input));
+        }
+        return Ok(hours * 60.0 + minutes);
+    }
+
+    let (whole, fraction) = input.split_once('.').unwrap_or((input, ""));
+    let has_digits = !whole.is_empty() || !fraction.is_empty();
+    let all_digits = whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit());
+    if !has_digits || !all_digits {
+        return Err(format!("\"{}\" is not a number of minutes", input));
+    }
+    input
+        .parse::<f32>()
+        .map_err(|_| format!("\"{}\" is not a number of minutes", input))
+}
+
+/// Parses the minutes for a manually added entry, which has to fit inside the day
+pub fn parse_manual_minutes(input: &str) -> Result<f32, String> {
+    let minutes = parse_minutes(input)?;
+    if minutes <= 0.0 || minutes >= 24.0 * 60.0 {
+        return Err(format!(
+            "{} minutes is outside the valid range, use more than 0 and less than 1440 (24 hours)",
+            minutes
+        ));
+    }
+    Ok(minutes)
+}
+
+/// Parses a duration like "1h30m", "45m" or "2.5h" into minutes
+fn parse_duration_token(token: &str) -> Option<f32> {
+    let mut minutes = 0.0;
+    let mut number = String::new();
+    let mut seen_units = String::new();
+    for c in token.to_ascii_lowercase().chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'h' | 'm' if !number.is_empty() && !seen_units.contains(c) => {
+                let value: f32 = number.parse().ok()?;
+                minutes += if c == 'h' { value * 60.0 } else { value };
+                seen_units.push(c);
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+    if number.is_empty() && !seen_units.is_empty() {
+        Some(minutes)
+    } else {
+        None
+    }
+}
+
+/// Parses a length of time in minutes, as plain minutes ("90"), hours and minutes
+/// ("1:30") or with units ("1h30m", "45m", "2.5h")
+pub fn parse_duration_minutes(input: &str) -> Result<f32, String> {
+    parse_minutes(input).or_else(|error| parse_duration_token(input.trim()).ok_or(error))
+}
+
+/// Parses a quick entry like "Dev 1h30m fixed the build" into an entry on `date` at
+/// `utc_offset`: a known project name, how long was worked, then the notes. Project names
+/// match ignoring case, and the longest match wins when one name starts another.
+pub fn parse_quick_entry(
+    input: &str,
+    project_types: &[String],
+    date: &NaiveDate,
+    utc_offset: &FixedOffset,
+) -> Result<TimeSheetEntry, String> {
+    let input = input.trim();
+    let project_type = project_types
+        .iter()
+        .filter(|project_type| {
+            input
+                .get(..project_type.len())
+                .map_or(false, |start| start.eq_ignore_ascii_case(project_type))
+                && input[project_type.len()..]
+                    .chars()
+                    .next()
+                    .map_or(true, char::is_whitespace)
+        })
+        .max_by_key(|project_type| project_type.len())
+        .ok_or_else(|| {
+            let typed = input.split_whitespace().next().unwrap_or_default();
+            format!("\"{}\" doesn't start with a known project", typed)
+        })?;
+    let rest = input[project_type.len()..].trim_start();
+    let (token, notes) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if token.is_empty() {
+        return Err("add how long was worked, like 1h30m, 45m or 2.5h".to_string());
+    }
+    let minutes = parse_duration_token(token).ok_or_else(|| {
+        format!(
+            "\"{}\" is not a duration, use something like 1h30m, 45m or 2.5h",
+            token
+        )
+    })?;
+    if minutes <= 0.0 || minutes >= 24.0 * 60.0 {
+        return Err(format!(
+            "{} is outside the valid range, use more than 0 and less than 24 hours",
+            token
+        ));
+    }
+    Ok(TimeSheetEntry::from_minutes(
+        project_type,
+        minutes,
+        notes.trim(),
+        date,
+        utc_offset,
+    ))
+}
+
+/// The last date of a period `days` long starting on `start_date`. Periods include both
+/// their start and end dates, so a 14 day period starting on the 1st ends on the 14th.
+pub fn period_end_date(start_date: NaiveDate, days: i64) -> NaiveDate {
+    start_date + Duration::days(days.max(1) - 1)
+}
+
+/// Checks a summary period runs forwards before it's summarized. A period can be a
+/// single day, starting and ending on the same date.
+pub fn validate_period(
+    start_date: &NaiveDate,
+    end_date: &NaiveDate,
+) -> Result<(NaiveDate, NaiveDate), String> {
+    if end_date < start_date {
+        return Err(format!(
+            "The period ends on {} before it starts on {}",
+            end_date.format("%F"),
+            start_date.format("%F")
+        ));
+    }
+    Ok((*start_date, *end_date))
+}
+
+/// Every date from `start_date` through `end_date` inclusive
+pub fn date_range(start_date: &NaiveDate, end_date: &NaiveDate) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+    let mut date = *start_date;
+    while date <= *end_date {
+        dates.push(date);
+        date = date.succ();
+    }
+    dates
+}
+
+/// The first day of the month `months` after the one containing `date`, counting
+/// backwards for negative `months`
+pub fn month_start(date: NaiveDate, months: i32) -> NaiveDate {
+    let month_index = date.year() * 12 + date.month0() as i32 + months;
+    NaiveDate::from_ymd(
+        month_index.div_euclid(12),
+        month_index.rem_euclid(12) as u32 + 1,
+        1,
+    )
+}
+
+/// Returns the first day of the week containing `date`, for weeks beginning on `week_start`
+pub fn week_start_date(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    let days_into_week =
+        (date.weekday().num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7;
+    date - Duration::days(days_into_week as i64)
+}
+
+pub const DEFAULT_WORKING_DAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+pub fn working_days_in(dates: &[NaiveDate], working_days: &[Weekday]) -> Vec<NaiveDate> {
+    dates
+        .iter()
+        .filter(|date| working_days.contains(&date.weekday()))
+        .copied()
+        .collect()
+}
+
+/// Hours expected over `dates` when working `weekly_hours` a week spread evenly
+/// across `working_days`
+pub fn expected_period_hours(
+    weekly_hours: f64,
+    dates: &[NaiveDate],
+    working_days: &[Weekday],
+) -> f64 {
+    if working_days.is_empty() {
+        return 0.0;
+    }
+    let hours_per_working_day = weekly_hours / working_days.len() as f64;
+    hours_per_working_day * working_days_in(dates, working_days).len() as f64
+}
+
+/// Shared by the tests of every module
+#[cfg(test)]
+pub(crate) fn datetime_from_ymd_hms(
+    year: i32,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+) -> DateTime<Utc> {
+    NaiveDate::from_ymd(year, month, day)
+        .and_hms(hour, minute, second)
+        .and_local_timezone(Utc)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_sheet_summary_empty_vec() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 12);
+        let end_date = NaiveDate::from_ymd(2022, 7, 13);
+        let empty_vec: Vec<TimeSheetEntry> = Vec::new();
+        let time_sheet_summary = TimeSheetSummary::new(&empty_vec, &start_date, &end_date);
+        assert_eq!(time_sheet_summary.summary.len(), 0);
+        assert_eq!(time_sheet_summary.dates.len(), 0);
+        assert_eq!(time_sheet_summary.projects.len(), 0);
+    }
+
+    #[test]
+    fn test_time_sheet_summary_one_item_vec() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 12);
+        let end_date = NaiveDate::from_ymd(2022, 7, 13);
+        let entries: Vec<TimeSheetEntry> = vec![TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 12, 2, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 4, 0, 0),
+            "",
+        )];
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        assert_eq!(time_sheet_summary.summary.len(), 1);
+        assert_eq!(time_sheet_summary.dates.len(), 1);
+        assert_eq!(time_sheet_summary.projects.len(), 1);
+
+        assert_eq!(
+            time_sheet_summary
+                .summary
+                .get(&NaiveDate::from_ymd(2022, 7, 12))
+                .unwrap()
+                .summary
+                .get("test")
+                .unwrap()
+                .hours_worked
+                .num_hours(),
+            2
+        )
+    }
+
+    #[test]
+    fn test_stored_entry_without_timestamps_defaults_to_work_start() {
+        let work_start_datetime = datetime_from_ymd_hms(2022, 7, 12, 2, 0, 0);
+        let entry = TimeSheetEntry::from(StoredTimeSheetEntry {
+            project_type: "test".to_string(),
+            work_start_datetime,
+            work_end_datetime: datetime_from_ymd_hms(2022, 7, 12, 4, 0, 0),
+            notes: String::new(),
+            created_at: None,
+            modified_at: None,
+            units: None,
+            tags: Vec::new(),
+            billable: true,
+        });
+        assert_eq!(entry.created_at, work_start_datetime);
+        assert_eq!(entry.modified_at, work_start_datetime);
+    }
+
+    #[test]
+    fn test_saved_entry_reads_back_its_timestamps() {
+        let mut entry = TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 12, 2, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 4, 0, 0),
+            "notes",
+        );
+        entry.created_at = datetime_from_ymd_hms(2022, 7, 12, 4, 0, 0);
+        entry.modified_at = datetime_from_ymd_hms(2022, 7, 13, 9, 0, 0);
+        let saved = ron::ser::to_string(&vec![entry]).unwrap();
+        let read: Vec<TimeSheetEntry> = ron::from_str(&saved).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(
+            read[0].created_at,
+            datetime_from_ymd_hms(2022, 7, 12, 4, 0, 0)
+        );
+        assert_eq!(
+            read[0].modified_at,
+            datetime_from_ymd_hms(2022, 7, 13, 9, 0, 0)
+        );
+        assert_eq!(read[0].notes, "notes");
+    }
+
+    #[test]
+    fn test_entry_saved_without_tags_loads() {
+        let entry: TimeSheetEntry = ron::from_str(
+            r#"(
+                project_type: "dev",
+                work_start_datetime: "2022-07-12T02:00:00Z",
+                work_end_datetime: "2022-07-12T04:00:00Z",
+                notes: "",
+            )"#,
+        )
+        .unwrap();
+        assert!(entry.tags.is_empty());
+        assert!(entry.billable);
+    }
+
+    #[test]
+    fn test_split_at_covers_the_original_span() {
+        let entry = TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 12, 0, 0),
+            "refactor",
+        );
+        let at = datetime_from_ymd_hms(2022, 7, 12, 10, 30, 0);
+        let (first, second) = entry.split_at(at, "meetings").unwrap();
+        assert_eq!(first.work_start_datetime, entry.work_start_datetime);
+        assert_eq!(first.work_end_datetime, at);
+        assert_eq!(second.work_start_datetime, at);
+        assert_eq!(second.work_end_datetime, entry.work_end_datetime);
+        assert_eq!(
+            (first.work_end_datetime - first.work_start_datetime)
+                + (second.work_end_datetime - second.work_start_datetime),
+            entry.work_end_datetime - entry.work_start_datetime
+        );
+        assert_eq!(first.project_type, "dev");
+        assert_eq!(second.project_type, "meetings");
+
+        assert!(entry
+            .split_at(datetime_from_ymd_hms(2022, 7, 12, 8, 0, 0), "meetings")
+            .is_err());
+        assert!(entry.split_at(entry.work_end_datetime, "meetings").is_err());
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags(" bugfix, review,,Bugfix , "),
+            vec!["bugfix".to_string(), "review".to_string()]
+        );
+        assert!(parse_tags("").is_empty());
+    }
+
+    #[test]
+    fn test_entries_by_tag_counts_each_tag() {
+        let mut tagged = TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0),
+            "",
+        );
+        tagged.tags = vec!["bugfix".to_string(), "review".to_string()];
+        let untagged = TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 11, 0, 0),
+            "",
+        );
+        let projects: Vec<String> = entries_by_tag(&[tagged, untagged])
+            .into_iter()
+            .map(|entry| entry.project_type)
+            .collect();
+        assert_eq!(projects, vec!["bugfix", "review", UNTAGGED]);
+    }
+
+    #[test]
+    fn test_uncategorized_entries() {
+        let project_types = vec!["dev".to_string()];
+        let mut entries = vec![
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "support",
+                datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 11, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "old",
+                datetime_from_ymd_hms(2022, 7, 12, 11, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 12, 0, 0),
+                "",
+            ),
+        ];
+        assert_eq!(
+            uncategorized_projects(&entries, &project_types),
+            vec!["old", "support"]
+        );
+        let summarized: Vec<String> = entries_with_uncategorized(&entries, &project_types)
+            .into_iter()
+            .map(|entry| entry.project_type)
+            .collect();
+        assert_eq!(summarized, vec!["dev", UNCATEGORIZED, UNCATEGORIZED]);
+
+        // The entry in the locked period keeps its project
+        let mut submissions = HashMap::new();
+        submissions.insert(
+            NaiveDate::from_ymd(2022, 7, 1),
+            PeriodSubmission {
+                period_end: NaiveDate::from_ymd(2022, 7, 10),
+                approved_by: String::new(),
+                submitted_on: datetime_from_ymd_hms(2022, 7, 11, 9, 0, 0),
+                locked: true,
+            },
+        );
+        entries.push(TimeSheetEntry::new(
+            "old",
+            datetime_from_ymd_hms(2022, 7, 8, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 8, 10, 0, 0),
+            "",
+        ));
+        assert_eq!(
+            reassign_uncategorized(
+                &mut entries,
+                &project_types,
+                "dev",
+                &submissions,
+                &FixedOffset::east(0)
+            ),
+            (2, 1)
+        );
+        assert!(entries[..3].iter().all(|entry| entry.project_type == "dev"));
+        assert_eq!(entries[3].project_type, "old");
+    }
+
+    #[test]
+    fn test_touch_updates_modified_at_only() {
+        let mut entry = TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 12, 2, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 4, 0, 0),
+            "",
+        );
+        let created_at = entry.created_at;
+        entry.modified_at = created_at - Duration::days(1);
+        entry.touch();
+        assert_eq!(entry.created_at, created_at);
+        assert!(entry.modified_at >= created_at);
+    }
+
+    #[test]
+    fn test_round_duration_up() {
+        let rounded = round_duration(
+            Duration::minutes(7),
+            Duration::minutes(15),
+            RoundingDirection::Up,
+        );
+        assert_eq!(rounded, Duration::minutes(15));
+    }
+
+    #[test]
+    fn test_round_duration_down() {
+        let rounded = round_duration(
+            Duration::minutes(7),
+            Duration::minutes(15),
+            RoundingDirection::Down,
+        );
+        assert_eq!(rounded, Duration::zero());
+    }
+
+    #[test]
+    fn test_round_duration_nearest() {
+        let rounded = round_duration(
+            Duration::minutes(7),
+            Duration::minutes(15),
+            RoundingDirection::Nearest,
+        );
+        assert_eq!(rounded, Duration::zero());
+    }
+
+    #[test]
+    fn test_from_minutes_spans_into_the_next_day() {
+        let date = NaiveDate::from_ymd(2022, 7, 12);
+        let utc = FixedOffset::east(0);
+        let entry = TimeSheetEntry::from_minutes("test", 90.5, "", &date, &utc);
+        assert_eq!(
+            entry.work_start_datetime,
+            datetime_from_ymd_hms(2022, 7, 12, 0, 0, 0)
+        );
+        assert_eq!(
+            entry.work_end_datetime,
+            datetime_from_ymd_hms(2022, 7, 12, 1, 30, 30)
+        );
+
+        let entry = TimeSheetEntry::from_minutes("test", 1440.0, "", &date, &utc);
+        assert_eq!(
+            entry.work_end_datetime,
+            datetime_from_ymd_hms(2022, 7, 13, 0, 0, 0)
+        );
+
+        let entry = TimeSheetEntry::from_minutes("test", 2000.0, "", &date, &utc);
+        assert_eq!(
+            entry.work_end_datetime,
+            datetime_from_ymd_hms(2022, 7, 13, 9, 20, 0)
+        );
+        assert_eq!(
+            entry.split_at_midnight(),
+            vec![
+                (NaiveDate::from_ymd(2022, 7, 12), Duration::minutes(1440)),
+                (NaiveDate::from_ymd(2022, 7, 13), Duration::minutes(560)),
+            ]
+        );
+
+        // Seconds rounding up to a whole minute
+        let entry = TimeSheetEntry::from_minutes("test", 59.999, "", &date, &utc);
+        assert_eq!(
+            entry.work_end_datetime,
+            datetime_from_ymd_hms(2022, 7, 12, 1, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_from_minutes_starts_at_local_midnight() {
+        let utc_offset = FixedOffset::west(7 * 3600);
+        let date = NaiveDate::from_ymd(2022, 7, 15);
+        let entry = TimeSheetEntry::from_minutes("test", 60.0, "", &date, &utc_offset);
+        assert_eq!(
+            entry.work_start_datetime,
+            datetime_from_ymd_hms(2022, 7, 15, 7, 0, 0)
+        );
+        assert_eq!(
+            entry.split_at_midnight_in(&utc_offset),
+            vec![(date, Duration::hours(1))]
+        );
+    }
+
+    #[test]
+    fn test_round_length() {
+        let rounding = RoundingSettings {
+            increment_minutes: 15,
+            direction: RoundingDirection::Up,
+        };
+        let mut entry = TimeSheetEntry::from_minutes(
+            "test",
+            7.0,
+            "",
+            &NaiveDate::from_ymd(2022, 7, 12),
+            &FixedOffset::east(0),
+        );
+        entry.round_length(&rounding);
+        assert_eq!(
+            entry.work_end_datetime - entry.work_start_datetime,
+            Duration::minutes(15)
+        );
+        let mut entry = TimeSheetEntry::from_minutes(
+            "test",
+            15.0,
+            "",
+            &NaiveDate::from_ymd(2022, 7, 12),
+            &FixedOffset::east(0),
+        );
+        entry.round_length(&rounding);
+        assert_eq!(
+            entry.work_end_datetime - entry.work_start_datetime,
+            Duration::minutes(15)
+        );
+        assert_eq!(
+            RoundingSettings::default().apply_to_entry(Duration::minutes(7)),
+            Duration::minutes(7)
+        );
+        // Rounding down or to the nearest never leaves a span with time in it empty
+        for direction in RoundingDirection::ALL {
+            let rounding = RoundingSettings {
+                increment_minutes: 30,
+                direction,
+            };
+            assert_eq!(
+                rounding.apply_to_entry(Duration::seconds(1)),
+                Duration::minutes(30)
+            );
+            assert_eq!(rounding.apply_to_entry(Duration::zero()), Duration::zero());
+        }
+    }
+
+    #[test]
+    fn test_snap_to_quarter_hour() {
+        assert_eq!(
+            snap_to_quarter_hour(Duration::minutes(22)),
+            Duration::minutes(15)
+        );
+        assert_eq!(
+            snap_to_quarter_hour(Duration::minutes(23)),
+            Duration::minutes(30)
+        );
+    }
+
+    #[test]
+    fn test_is_date_locked() {
+        let mut submissions = HashMap::new();
+        submissions.insert(
+            NaiveDate::from_ymd(2022, 7, 1),
+            PeriodSubmission {
+                period_end: NaiveDate::from_ymd(2022, 7, 14),
+                approved_by: "manager".to_string(),
+                submitted_on: datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+                locked: true,
+            },
+        );
+        submissions.insert(
+            NaiveDate::from_ymd(2022, 7, 15),
+            PeriodSubmission {
+                period_end: NaiveDate::from_ymd(2022, 7, 28),
+                approved_by: String::new(),
+                submitted_on: datetime_from_ymd_hms(2022, 7, 29, 9, 0, 0),
+                locked: false,
+            },
+        );
+        assert!(is_date_locked(
+            &submissions,
+            NaiveDate::from_ymd(2022, 7, 1)
+        ));
+        assert!(is_date_locked(
+            &submissions,
+            NaiveDate::from_ymd(2022, 7, 14)
+        ));
+        assert!(!is_date_locked(
+            &submissions,
+            NaiveDate::from_ymd(2022, 7, 20)
+        ));
+        assert!(!is_date_locked(
+            &submissions,
+            NaiveDate::from_ymd(2022, 6, 30)
+        ));
+    }
+
+    #[test]
+    fn test_expected_period_hours() {
+        // 2022-07-11 is a Monday
+        let two_weeks = date_range(
+            &NaiveDate::from_ymd(2022, 7, 11),
+            &NaiveDate::from_ymd(2022, 7, 24),
+        );
+        assert_eq!(
+            expected_period_hours(40.0, &two_weeks, &DEFAULT_WORKING_DAYS),
+            80.0
+        );
+        assert_eq!(
+            expected_period_hours(40.0, &two_weeks[..7], &DEFAULT_WORKING_DAYS),
+            40.0
+        );
+        assert_eq!(
+            expected_period_hours(40.0, &two_weeks[..7], &[Weekday::Mon, Weekday::Tue]),
+            40.0
+        );
+        assert_eq!(
+            expected_period_hours(40.0, &two_weeks[5..7], &DEFAULT_WORKING_DAYS),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_average_per_working_day_excludes_non_working_days() {
+        // 2022-07-11 is a Monday, 2022-07-16 a Saturday
+        let start_date = NaiveDate::from_ymd(2022, 7, 11);
+        let end_date = NaiveDate::from_ymd(2022, 7, 17);
+        let entries = vec![
+            TimeSheetEntry::new(
+                "test",
+                datetime_from_ymd_hms(2022, 7, 11, 8, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 11, 18, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "test",
+                datetime_from_ymd_hms(2022, 7, 16, 8, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 16, 12, 0, 0),
+                "",
+            ),
+        ];
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        assert_eq!(
+            time_sheet_summary.average_per_working_day(&DEFAULT_WORKING_DAYS),
+            Duration::hours(2)
+        );
+        assert_eq!(
+            time_sheet_summary.missing_working_days(&DEFAULT_WORKING_DAYS),
+            date_range(
+                &NaiveDate::from_ymd(2022, 7, 12),
+                &NaiveDate::from_ymd(2022, 7, 15)
+            )
+        );
+    }
+
+    #[test]
+    fn test_total_hours_worked() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 12);
+        let end_date = NaiveDate::from_ymd(2022, 7, 13);
+        let entries = vec![
+            TimeSheetEntry::new(
+                "test",
+                datetime_from_ymd_hms(2022, 7, 12, 2, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 4, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "other",
+                datetime_from_ymd_hms(2022, 7, 13, 2, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 13, 3, 30, 0),
+                "",
+            ),
+        ];
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        assert_eq!(
+            time_sheet_summary.total_hours_worked(),
+            Duration::minutes(210)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_on_keeps_time_of_day_and_length() {
+        let standup = TimeSheetEntry::new(
+            "Meetings",
+            datetime_from_ymd_hms(2022, 7, 12, 16, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 16, 15, 0),
+            "standup",
+        );
+        // 16:00 UTC is 9am at UTC-7
+        let duplicate = standup.duplicate_on(
+            NaiveDate::from_ymd(2022, 7, 20),
+            &FixedOffset::west(7 * 3600),
+        );
+        assert_eq!(
+            duplicate.work_start_datetime,
+            datetime_from_ymd_hms(2022, 7, 20, 16, 0, 0)
+        );
+        assert_eq!(
+            duplicate.work_end_datetime,
+            datetime_from_ymd_hms(2022, 7, 20, 16, 15, 0)
+        );
+        assert_eq!(duplicate.project_type, "Meetings");
+        assert_eq!(duplicate.notes, "standup");
+    }
+
+    #[test]
+    fn test_weekday_totals() {
+        let entries = vec![
+            // Two Mondays
+            TimeSheetEntry::new(
+                "Meetings",
+                datetime_from_ymd_hms(2022, 7, 4, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 4, 11, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "Meetings",
+                datetime_from_ymd_hms(2022, 7, 11, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 11, 10, 0, 0),
+                "",
+            ),
+            // Saturday night into Sunday
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 9, 23, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 10, 1, 0, 0),
+                "",
+            ),
+        ];
+        let totals = weekday_totals(&entries, Weekday::Sun, &FixedOffset::east(0));
+        assert_eq!(totals.len(), 7);
+        assert_eq!(totals[0], (Weekday::Sun, Duration::hours(1)));
+        assert_eq!(totals[1], (Weekday::Mon, Duration::hours(3)));
+        assert_eq!(totals[2], (Weekday::Tue, Duration::zero()));
+        assert_eq!(totals[6], (Weekday::Sat, Duration::hours(1)));
+    }
+
+    #[test]
+    fn test_copy_day() {
+        let pacific = FixedOffset::west(7 * 3600);
+        let entries = vec![
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 12, 18, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 20, 0, 0),
+                "build",
+            ),
+            TimeSheetEntry::new(
+                "Meetings",
+                datetime_from_ymd_hms(2022, 7, 12, 16, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 16, 15, 0),
+                "standup",
+            ),
+            // 02:00 UTC on the 13th is still the 12th at UTC-7
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 13, 2, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 13, 3, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 11, 16, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 11, 17, 0, 0),
+                "",
+            ),
+        ];
+        let copies = copy_day(
+            &entries,
+            NaiveDate::from_ymd(2022, 7, 12),
+            NaiveDate::from_ymd(2022, 7, 13),
+            &pacific,
+        );
+        assert_eq!(copies.len(), 3);
+        assert_eq!(copies[0].notes, "standup");
+        assert_eq!(
+            copies[0].work_start_datetime,
+            datetime_from_ymd_hms(2022, 7, 13, 16, 0, 0)
+        );
+        assert_eq!(
+            copies[2].work_start_datetime,
+            datetime_from_ymd_hms(2022, 7, 14, 2, 0, 0)
+        );
+        assert!(copy_day(
+            &entries,
+            NaiveDate::from_ymd(2022, 7, 10),
+            NaiveDate::from_ymd(2022, 7, 13),
+            &pacific
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let entry = TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 12, 0, 0),
+            "",
+        );
+        let adjacent = TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 12, 12, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 13, 0, 0),
+            "",
+        );
+        let contained = TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 11, 0, 0),
+            "",
+        );
+        let identical = entry.clone();
+        assert!(!entry.overlaps(&adjacent));
+        assert!(!adjacent.overlaps(&entry));
+        assert!(entry.overlaps(&contained));
+        assert!(contained.overlaps(&entry));
+        assert!(entry.overlaps(&identical));
+    }
+
+    #[test]
+    fn test_overlapping_entries_compares_within_a_project() {
+        let entry = |project: &str, start_hour: u32, end_hour: u32| {
+            TimeSheetEntry::new(
+                project,
+                datetime_from_ymd_hms(2022, 7, 12, start_hour, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, end_hour, 0, 0),
+                "",
+            )
+        };
+        let entries = vec![
+            entry("dev", 9, 17),
+            entry("dev", 10, 11),
+            entry("dev", 17, 18),
+            // Running alongside dev is fine
+            entry("support", 12, 13),
+            entry("support", 14, 15),
+            entry("dev", 16, 19),
+        ];
+        let mut overlapping: Vec<usize> = overlapping_entries(&entries).into_iter().collect();
+        overlapping.sort_unstable();
+        assert_eq!(overlapping, vec![0, 1, 2, 5]);
+    }
+
+    #[test]
+    fn test_project_totals_span_all_dates() {
+        let entries = vec![
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2021, 1, 4, 9, 0, 0),
+                datetime_from_ymd_hms(2021, 1, 4, 10, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "Meetings",
+                datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 9, 30, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 12, 0, 0),
+                "",
+            ),
+        ];
+        assert_eq!(
+            project_totals(&entries),
+            vec![
+                ("dev".to_string(), Duration::hours(3)),
+                ("Meetings".to_string(), Duration::minutes(30)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recent_notes() {
+        let mut entries: Vec<TimeSheetEntry> =
+            ["standup", "", "review", "standup", " review ", "build"]
+                .iter()
+                .enumerate()
+                .map(|(index, notes)| {
+                    let mut entry = TimeSheetEntry::new(
+                        "dev",
+                        datetime_from_ymd_hms(2022, 7, 1, 9, 0, 0),
+                        datetime_from_ymd_hms(2022, 7, 1, 10, 0, 0),
+                        notes,
+                    );
+                    entry.created_at = datetime_from_ymd_hms(2022, 7, 1, 10, index as u32, 0);
+                    entry
+                })
+                .collect();
+        assert_eq!(
+            recent_notes(&entries, 10),
+            vec!["build", "review", "standup"]
+        );
+        assert_eq!(recent_notes(&entries, 2), vec!["build", "review"]);
+
+        // Entries are ordered by when they were created, not where they are in the list
+        entries[0].created_at = datetime_from_ymd_hms(2022, 7, 2, 10, 0, 0);
+        assert_eq!(recent_notes(&entries, 1), vec!["standup"]);
+    }
+
+    #[test]
+    fn test_add_project_type() {
+        let mut project_types = vec!["Meetings".to_string()];
+        assert_eq!(
+            add_project_type(&mut project_types, "  dev "),
+            Ok("dev".to_string())
+        );
+        assert!(add_project_type(&mut project_types, "").is_err());
+        assert!(add_project_type(&mut project_types, "   ").is_err());
+        assert!(add_project_type(&mut project_types, "meetings").is_err());
+        assert!(add_project_type(&mut project_types, "DEV").is_err());
+        assert_eq!(project_types, vec!["Meetings", "dev"]);
+    }
+
+    #[test]
+    fn test_rename_project_updates_entries() {
+        let start = datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0);
+        let end = datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0);
+        let mut project_types = vec!["Lunch".to_string(), "Meetings".to_string()];
+        let mut entries = vec![
+            TimeSheetEntry::new("Meetings", start, end, ""),
+            TimeSheetEntry::new("Lunch", start, end, ""),
+            TimeSheetEntry::new("Meetings", start, end, ""),
+        ];
+        let renamed = rename_project(
+            &mut project_types,
+            &mut entries,
+            "Meetings",
+            "Internal Meetings",
+        );
+        assert_eq!(renamed, 2);
+        assert_eq!(project_types, vec!["Lunch", "Internal Meetings"]);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            entries
+                .iter()
+                .filter(|entry| entry.project_type == "Internal Meetings")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_rename_project_to_existing_name_merges() {
+        let start = datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0);
+        let end = datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0);
+        let mut project_types = vec!["Meating".to_string(), "Meetings".to_string()];
+        let mut entries = vec![
+            TimeSheetEntry::new("Meating", start, end, ""),
+            TimeSheetEntry::new("Meetings", start, end, ""),
+        ];
+        assert_eq!(
+            rename_project(&mut project_types, &mut entries, "Meating", "Meetings"),
+            1
+        );
+        assert_eq!(project_types, vec!["Meetings"]);
+        assert!(entries.iter().all(|entry| entry.project_type == "Meetings"));
+    }
+
+    #[test]
+    fn test_rename_project_merges_ignoring_case() {
+        let start = datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0);
+        let end = datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0);
+        let mut project_types = vec!["dev".to_string(), "support".to_string()];
+        let mut entries = vec![
+            TimeSheetEntry::new("dev", start, end, ""),
+            TimeSheetEntry::new("support", start, end, ""),
+        ];
+        // Only changing the case renames the project itself
+        assert_eq!(merge_target(&project_types, "dev", "Dev"), None);
+        rename_project(&mut project_types, &mut entries, "dev", "Dev");
+        assert_eq!(project_types, vec!["Dev", "support"]);
+
+        // Another project's name in a different case merges into its spelling
+        assert_eq!(
+            rename_project(&mut project_types, &mut entries, "support", "DEV"),
+            1
+        );
+        assert_eq!(project_types, vec!["Dev"]);
+        assert!(entries.iter().all(|entry| entry.project_type == "Dev"));
+    }
+
+    #[test]
+    fn test_merge_entries_skips_duplicates() {
+        let entry = TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 12, 0, 0),
+            "notes",
+        );
+        let mut different_notes = entry.clone();
+        different_notes.notes = "other notes".to_string();
+        let mut entries = vec![entry.clone()];
+        let mut duplicate = entry;
+        duplicate.touch();
+        let (added, skipped) = merge_entries(
+            &mut entries,
+            vec![duplicate, different_notes.clone(), different_notes],
+        );
+        assert_eq!((added, skipped), (1, 2));
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[test]
+    fn test_without_locked_entries() {
+        let mut submissions = HashMap::new();
+        submissions.insert(
+            NaiveDate::from_ymd(2022, 7, 1),
+            PeriodSubmission {
+                period_end: NaiveDate::from_ymd(2022, 7, 14),
+                approved_by: String::new(),
+                submitted_on: datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+                locked: true,
+            },
+        );
+        let imported = vec![
+            TimeSheetEntry::new(
+                "test",
+                datetime_from_ymd_hms(2022, 7, 14, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 14, 10, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "test",
+                datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 15, 10, 0, 0),
+                "",
+            ),
+        ];
+        let (unlocked, locked) =
+            without_locked_entries(imported, &submissions, &FixedOffset::east(0));
+        assert_eq!(locked, 1);
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(
+            unlocked[0].work_start_datetime,
+            datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_split_at_midnight() {
+        let entry = TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 12, 22, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 13, 1, 30, 0),
+            "",
+        );
+        assert_eq!(
+            entry.split_at_midnight(),
+            vec![
+                (NaiveDate::from_ymd(2022, 7, 12), Duration::hours(2)),
+                (NaiveDate::from_ymd(2022, 7, 13), Duration::minutes(90)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_time_sheet_summary_buckets_by_local_day() {
+        // 9pm on the 15th in UTC-7 is 4am on the 16th in UTC
+        let entries = vec![TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 16, 4, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 16, 5, 0, 0),
+            "",
+        )];
+        let local_date = NaiveDate::from_ymd(2022, 7, 15);
+        let utc_offset = FixedOffset::west(7 * 3600);
+        let time_sheet_summary =
+            TimeSheetSummary::new_in(&entries, &local_date, &local_date, &utc_offset);
+        assert_eq!(time_sheet_summary.dates, vec![local_date]);
+        assert_eq!(
+            time_sheet_summary.hours_worked(&local_date, "test"),
+            Duration::hours(1)
+        );
+        let utc_summary = TimeSheetSummary::new(&entries, &local_date, &local_date);
+        assert!(utc_summary.dates.is_empty());
+    }
+
+    #[test]
+    fn test_time_sheet_summary_splits_entries_across_days() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 12);
+        let end_date = NaiveDate::from_ymd(2022, 7, 13);
+        let entries = vec![TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2022, 7, 12, 22, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 13, 1, 30, 0),
+            "late night",
+        )];
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        assert_eq!(time_sheet_summary.dates, vec![start_date, end_date]);
+        let first_day = &time_sheet_summary.summary[&start_date].summary["test"];
+        let second_day = &time_sheet_summary.summary[&end_date].summary["test"];
+        assert_eq!(first_day.hours_worked, Duration::hours(2));
+        assert_eq!(second_day.hours_worked, Duration::minutes(90));
+        assert!(second_day.notes.is_empty());
+    }
+
+    #[test]
+    fn test_time_sheet_summary_joins_notes_without_leading_separator() {
+        let date = NaiveDate::from_ymd(2022, 7, 12);
+        let standup = TimeSheetEntry::new(
+            "meetings",
+            datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 9, 15, 0),
+            "standup",
+        );
+        let single_note = TimeSheetSummary::new(std::slice::from_ref(&standup), &date, &date);
+        assert_eq!(
+            single_note.summary[&date].summary["meetings"].notes,
+            "standup"
+        );
+
+        let planning = TimeSheetEntry::new(
+            "meetings",
+            datetime_from_ymd_hms(2022, 7, 12, 10, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 11, 0, 0),
+            "planning",
+        );
+        let two_notes = TimeSheetSummary::new(&[standup, planning], &date, &date);
+        assert_eq!(
+            two_notes.summary[&date].summary["meetings"].notes,
+            "standup\nplanning"
+        );
+    }
+
+    #[test]
+    fn test_week_start_date() {
+        // 2022-07-13 is a Wednesday
+        let date = NaiveDate::from_ymd(2022, 7, 13);
+        assert_eq!(
+            week_start_date(date, Weekday::Mon),
+            NaiveDate::from_ymd(2022, 7, 11)
+        );
+        assert_eq!(
+            week_start_date(date, Weekday::Sun),
+            NaiveDate::from_ymd(2022, 7, 10)
+        );
+        assert_eq!(week_start_date(date, Weekday::Wed), date);
+    }
+
+    #[test]
+    fn test_day_project_totals_sorted_descending() {
+        let date = NaiveDate::from_ymd(2022, 7, 12);
+        let entries = vec![
+            TimeSheetEntry::new(
+                "short",
+                datetime_from_ymd_hms(2022, 7, 12, 2, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 2, 30, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "long",
+                datetime_from_ymd_hms(2022, 7, 12, 3, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 5, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "short",
+                datetime_from_ymd_hms(2022, 7, 13, 3, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 13, 9, 0, 0),
+                "",
+            ),
+        ];
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &date, &date);
+        assert_eq!(
+            time_sheet_summary.day_project_totals(&date),
+            vec![
+                ("long".to_string(), Duration::hours(2)),
+                ("short".to_string(), Duration::minutes(30)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_date_range_across_leap_day() {
+        let dates = date_range(
+            &NaiveDate::from_ymd(2024, 2, 28),
+            &NaiveDate::from_ymd(2024, 3, 1),
+        );
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd(2024, 2, 28),
+                NaiveDate::from_ymd(2024, 2, 29),
+                NaiveDate::from_ymd(2024, 3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_month_start() {
+        let date = NaiveDate::from_ymd(2022, 12, 15);
+        assert_eq!(month_start(date, 0), NaiveDate::from_ymd(2022, 12, 1));
+        assert_eq!(month_start(date, 1), NaiveDate::from_ymd(2023, 1, 1));
+        assert_eq!(month_start(date, -12), NaiveDate::from_ymd(2021, 12, 1));
+        assert_eq!(
+            month_start(NaiveDate::from_ymd(2022, 1, 31), -1),
+            NaiveDate::from_ymd(2021, 12, 1)
+        );
+    }
+
+    #[test]
+    fn test_date_range_across_new_year() {
+        let dates = date_range(
+            &NaiveDate::from_ymd(2022, 12, 30),
+            &NaiveDate::from_ymd(2023, 1, 2),
+        );
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd(2022, 12, 30),
+                NaiveDate::from_ymd(2022, 12, 31),
+                NaiveDate::from_ymd(2023, 1, 1),
+                NaiveDate::from_ymd(2023, 1, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_time_sheet_summary_period_dates_include_gaps() {
+        let start_date = NaiveDate::from_ymd(2024, 2, 27);
+        let end_date = NaiveDate::from_ymd(2024, 3, 1);
+        let entries = vec![TimeSheetEntry::new(
+            "test",
+            datetime_from_ymd_hms(2024, 2, 27, 2, 0, 0),
+            datetime_from_ymd_hms(2024, 2, 27, 4, 0, 0),
+            "",
+        )];
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        assert_eq!(time_sheet_summary.dates, vec![start_date]);
+        assert_eq!(time_sheet_summary.period_dates.len(), 4);
+        assert_eq!(
+            time_sheet_summary.period_dates[2],
+            NaiveDate::from_ymd(2024, 2, 29)
+        );
+    }
+
+    #[test]
+    fn test_parse_minutes_accepts_decimal_and_colon_formats() {
+        assert_eq!(parse_minutes("90"), Ok(90.0));
+        assert_eq!(parse_minutes(" 7.5 "), Ok(7.5));
+        assert_eq!(parse_minutes("1:30"), Ok(90.0));
+    }
+
+    #[test]
+    fn test_parse_manual_minutes_range() {
+        assert_eq!(parse_manual_minutes("90"), Ok(90.0));
+        assert_eq!(parse_manual_minutes("23:59"), Ok(1439.0));
+        assert!(parse_manual_minutes("0").is_err());
+        assert!(parse_manual_minutes("1440").is_err());
+        assert!(parse_manual_minutes("lots").is_err());
+    }
+
+    #[test]
+    fn test_parse_quick_entry() {
+        let project_types = vec![
+            "Dev".to_string(),
+            "Dev Ops".to_string(),
+            "Meetings".to_string(),
+        ];
+        let date = NaiveDate::from_ymd(2022, 7, 15);
+        let utc_offset = FixedOffset::west(4 * 3600);
+        let parse_quick_entry = |input: &str, project_types: &[String]| {
+            parse_quick_entry(input, project_types, &date, &utc_offset)
+        };
+        let minutes = |entry: &TimeSheetEntry| {
+            (entry.work_end_datetime - entry.work_start_datetime).num_minutes()
+        };
+
+        let entry = parse_quick_entry("Dev 1h30m fixed the build", &project_types).unwrap();
+        assert_eq!(entry.project_type, "Dev");
+        assert_eq!(minutes(&entry), 90);
+        assert_eq!(entry.notes, "fixed the build");
+        assert_eq!(
+            entry.work_start_datetime,
+            datetime_from_ymd_hms(2022, 7, 15, 4, 0, 0)
+        );
+
+        let entry = parse_quick_entry("meetings 45m", &project_types).unwrap();
+        assert_eq!(entry.project_type, "Meetings");
+        assert_eq!(minutes(&entry), 45);
+        assert_eq!(entry.notes, "");
+
+        let entry = parse_quick_entry("Dev Ops 2.5h  deploys ", &project_types).unwrap();
+        assert_eq!(entry.project_type, "Dev Ops");
+        assert_eq!(minutes(&entry), 150);
+        assert_eq!(entry.notes, "deploys");
+
+        let entry = parse_quick_entry("Dev 2H", &project_types).unwrap();
+        assert_eq!(minutes(&entry), 120);
+    }
+
+    #[test]
+    fn test_parse_duration_minutes() {
+        assert_eq!(parse_duration_minutes("90"), Ok(90.0));
+        assert_eq!(parse_duration_minutes(" 1:30 "), Ok(90.0));
+        assert_eq!(parse_duration_minutes("1h30m"), Ok(90.0));
+        assert_eq!(parse_duration_minutes("2.5h"), Ok(150.0));
+        assert!(parse_duration_minutes("1:75").is_err());
+        assert!(parse_duration_minutes("soon").is_err());
+    }
+
+    #[test]
+    fn test_parse_quick_entry_errors() {
+        let project_types = vec!["Dev".to_string()];
+        let date = NaiveDate::from_ymd(2022, 7, 15);
+        let utc_offset = FixedOffset::east(0);
+        let parse_quick_entry = |input: &str, project_types: &[String]| {
+            parse_quick_entry(input, project_types, &date, &utc_offset)
+        };
+        assert!(parse_quick_entry("Design 1h", &project_types).is_err());
+        assert!(parse_quick_entry("Devs 1h", &project_types).is_err());
+        assert!(parse_quick_entry("Dev", &project_types).is_err());
+        assert!(parse_quick_entry("Dev fixed the build", &project_types).is_err());
+        assert!(parse_quick_entry("Dev 1h1h", &project_types).is_err());
+        assert!(parse_quick_entry("Dev 90", &project_types).is_err());
+        assert!(parse_quick_entry("Dev 0m", &project_types).is_err());
+        assert!(parse_quick_entry("Dev 24h", &project_types).is_err());
+    }
+
+    #[test]
+    fn test_parse_minutes_rejects_scientific_notation_nan_and_infinity() {
+        assert!(parse_minutes("1e3").is_err());
+        assert!(parse_minutes("NaN").is_err());
+        assert!(parse_minutes("inf").is_err());
+        assert!(parse_minutes("").is_err());
+        assert!(parse_minutes(".").is_err());
+        assert!(parse_minutes("1:75").is_err());
+    }
+
+    #[test]
+    fn test_time_sheet_summary_totals_units() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 12);
+        let end_date = NaiveDate::from_ymd(2022, 7, 13);
+        let mut first = TimeSheetEntry::new(
+            "support",
+            datetime_from_ymd_hms(2022, 7, 12, 2, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 4, 0, 0),
+            "",
+        );
+        first.units = Some(4.0);
+        let mut second = TimeSheetEntry::new(
+            "support",
+            datetime_from_ymd_hms(2022, 7, 13, 2, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 13, 4, 0, 0),
+            "",
+        );
+        second.units = Some(2.5);
+        let untracked = TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 13, 5, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 13, 6, 0, 0),
+            "",
+        );
+        let entries = vec![first, second, untracked];
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        assert_eq!(time_sheet_summary.project_units.len(), 1);
+        assert_eq!(time_sheet_summary.project_units["support"], 6.5);
+    }
+
+    #[test]
+    fn test_time_sheet_summary_costs_skip_projects_without_rate() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 12);
+        let end_date = NaiveDate::from_ymd(2022, 7, 13);
+        let entries = vec![
+            TimeSheetEntry::new(
+                "client work",
+                datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 11, 30, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "client work",
+                datetime_from_ymd_hms(2022, 7, 13, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 13, 10, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "internal",
+                datetime_from_ymd_hms(2022, 7, 13, 10, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 13, 12, 0, 0),
+                "",
+            ),
+        ];
+        let mut hourly_rates = HashMap::new();
+        hourly_rates.insert("client work".to_string(), 80.0);
+        let rounding = RoundingSettings::default();
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        assert_eq!(
+            time_sheet_summary.project_total("client work", &rounding),
+            Duration::minutes(210)
+        );
+        assert_eq!(
+            time_sheet_summary.project_cost("client work", &hourly_rates, &rounding),
+            Some(280.0)
+        );
+        assert_eq!(
+            time_sheet_summary.project_cost("internal", &hourly_rates, &rounding),
+            None
+        );
+        assert_eq!(
+            time_sheet_summary.total_cost(&hourly_rates, &rounding),
+            280.0
+        );
+    }
+
+    #[test]
+    fn test_non_billable_entries_are_left_out_of_costs() {
+        let date = NaiveDate::from_ymd(2022, 7, 12);
+        let billable = TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 11, 0, 0),
+            "",
+        );
+        let mut non_billable = TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 12, 11, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 12, 12, 0, 0),
+            "internal tooling",
+        );
+        non_billable.billable = false;
+        let mut hourly_rates = HashMap::new();
+        hourly_rates.insert("dev".to_string(), 50.0);
+        let rounding = RoundingSettings::default();
+        let time_sheet_summary = TimeSheetSummary::new(&[billable, non_billable], &date, &date);
+        assert_eq!(
+            time_sheet_summary.project_total("dev", &rounding),
+            Duration::hours(3)
+        );
+        assert_eq!(
+            time_sheet_summary.project_billable_total("dev", &rounding),
+            Duration::hours(2)
+        );
+        assert_eq!(
+            time_sheet_summary.billable_day_total(&date, &rounding),
+            Duration::hours(2)
+        );
+        assert_eq!(
+            time_sheet_summary.project_cost("dev", &hourly_rates, &rounding),
+            Some(100.0)
+        );
+    }
+
+    #[test]
+    fn test_client_totals_group_projects_by_client() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 12);
+        let end_date = NaiveDate::from_ymd(2022, 7, 13);
+        let entries = vec![
+            TimeSheetEntry::new(
+                "acme design",
+                datetime_from_ymd_hms(2022, 7, 12, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 12, 11, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "acme support",
+                datetime_from_ymd_hms(2022, 7, 13, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 13, 10, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "internal",
+                datetime_from_ymd_hms(2022, 7, 13, 10, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 13, 12, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "globex",
+                datetime_from_ymd_hms(2022, 7, 13, 13, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 13, 14, 0, 0),
+                "",
+            ),
+        ];
+        let mut project_clients = HashMap::new();
+        project_clients.insert("acme design".to_string(), "Acme".to_string());
+        project_clients.insert("acme support".to_string(), "Acme".to_string());
+        project_clients.insert("globex".to_string(), "Globex".to_string());
+        let mut hourly_rates = HashMap::new();
+        hourly_rates.insert("acme design".to_string(), 100.0);
+        hourly_rates.insert("acme support".to_string(), 50.0);
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        let clients = time_sheet_summary.client_totals(
+            &project_clients,
+            &hourly_rates,
+            &RoundingSettings::default(),
+        );
+        assert_eq!(clients.len(), 3);
+        assert_eq!(clients[0].client.as_deref(), Some("Acme"));
+        assert_eq!(
+            clients[0].projects,
+            vec![
+                ("acme design".to_string(), Duration::hours(2), Some(200.0)),
+                ("acme support".to_string(), Duration::hours(1), Some(50.0)),
+            ]
+        );
+        assert_eq!(clients[0].hours, Duration::hours(3));
+        assert_eq!(clients[0].cost, 250.0);
+        assert_eq!(clients[1].client.as_deref(), Some("Globex"));
+        assert_eq!(clients[1].cost, 0.0);
+        assert_eq!(clients[2].client, None);
+        assert_eq!(clients[2].hours, Duration::hours(2));
+    }
+
+    #[test]
+    fn test_week_to_date_totals_restart_each_week() {
+        // 2022-07-15 is a Friday
+        let start_date = NaiveDate::from_ymd(2022, 7, 15);
+        let end_date = NaiveDate::from_ymd(2022, 7, 19);
+        let entries = vec![
+            TimeSheetEntry::new(
+                "test",
+                datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 15, 11, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "test",
+                datetime_from_ymd_hms(2022, 7, 16, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 16, 10, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "test",
+                datetime_from_ymd_hms(2022, 7, 18, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 18, 12, 0, 0),
+                "",
+            ),
+        ];
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        assert_eq!(
+            time_sheet_summary.week_to_date_totals(Weekday::Mon, &RoundingSettings::default()),
+            vec![
+                Duration::hours(2),
+                Duration::hours(3),
+                Duration::hours(3),
+                Duration::hours(3),
+                Duration::hours(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_period() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 12);
+        let end_date = NaiveDate::from_ymd(2022, 7, 26);
+        assert_eq!(
+            validate_period(&start_date, &end_date),
+            Ok((start_date, end_date))
+        );
+        assert!(validate_period(&end_date, &start_date).is_err());
+        assert_eq!(
+            validate_period(&start_date, &start_date),
+            Ok((start_date, start_date))
+        );
+    }
+
+    #[test]
+    fn test_summary_from_sub_slice_and_filtered_entries() {
+        let entries: Vec<TimeSheetEntry> = (1..=4)
+            .map(|day| {
+                TimeSheetEntry::new(
+                    if day % 2 == 0 { "dev" } else { "Meetings" },
+                    datetime_from_ymd_hms(2022, 7, day, 9, 0, 0),
+                    datetime_from_ymd_hms(2022, 7, day, 10, 0, 0),
+                    "",
+                )
+            })
+            .collect();
+        let start_date = NaiveDate::from_ymd(2022, 7, 1);
+        let end_date = NaiveDate::from_ymd(2022, 7, 14);
+
+        let time_sheet_summary = TimeSheetSummary::new(&entries[1..3], &start_date, &end_date);
+        assert_eq!(
+            time_sheet_summary.dates,
+            vec![
+                NaiveDate::from_ymd(2022, 7, 2),
+                NaiveDate::from_ymd(2022, 7, 3)
+            ]
+        );
+        assert_eq!(time_sheet_summary.grand_total(), Duration::hours(2));
+
+        let time_sheet_summary = TimeSheetSummary::from_entries_in(
+            entries.iter().filter(|entry| entry.project_type == "dev"),
+            &start_date,
+            &end_date,
+            &FixedOffset::east(0),
+        );
+        assert_eq!(time_sheet_summary.projects, vec!["dev"]);
+        assert_eq!(time_sheet_summary.grand_total(), Duration::hours(2));
+    }
+
+    #[test]
+    fn test_excluded_projects_are_left_out_of_totals() {
+        let entries = vec![
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 4, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 4, 12, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "Lunch",
+                datetime_from_ymd_hms(2022, 7, 4, 12, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 4, 13, 0, 0),
+                "",
+            ),
+        ];
+        let date = NaiveDate::from_ymd(2022, 7, 4);
+        let mut time_sheet_summary = TimeSheetSummary::new(&entries, &date, &date);
+        time_sheet_summary
+            .excluded_from_totals
+            .insert("Lunch".to_string());
+        let hourly_rates = HashMap::from([("dev".to_string(), 10.0), ("Lunch".to_string(), 10.0)]);
+        let rounding = RoundingSettings::default();
+
+        assert_eq!(time_sheet_summary.projects.len(), 2);
+        assert_eq!(
+            time_sheet_summary.hours_worked(&date, "Lunch"),
+            Duration::hours(1)
+        );
+        assert_eq!(time_sheet_summary.day_total(&date), Duration::hours(3));
+        assert_eq!(time_sheet_summary.total_hours_worked(), Duration::hours(3));
+        assert_eq!(
+            time_sheet_summary.week_to_date_totals(Weekday::Mon, &rounding),
+            vec![Duration::hours(3)]
+        );
+        assert_eq!(
+            time_sheet_summary.total_cost(&hourly_rates, &rounding),
+            30.0
+        );
+        assert_eq!(
+            time_sheet_summary.total_hours_per_project()["Lunch"],
+            Duration::hours(1)
+        );
+    }
+
+    #[test]
+    fn test_summary_totals_per_project() {
+        let entries = vec![
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 1, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 1, 10, 30, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 2, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 2, 10, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "Meetings",
+                datetime_from_ymd_hms(2022, 7, 2, 11, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 2, 11, 15, 0),
+                "",
+            ),
+        ];
+        let time_sheet_summary = TimeSheetSummary::new(
+            &entries,
+            &NaiveDate::from_ymd(2022, 7, 1),
+            &NaiveDate::from_ymd(2022, 7, 14),
+        );
+        let totals = time_sheet_summary.total_hours_per_project();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals["dev"], Duration::minutes(150));
+        assert_eq!(totals["Meetings"], Duration::minutes(15));
+        assert_eq!(time_sheet_summary.grand_total(), Duration::minutes(165));
+    }
+
+    #[test]
+    fn test_summary_from_file() {
+        let entries = vec![TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 1, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 1, 10, 0, 0),
+            "",
+        )];
+        let path =
+            std::env::temp_dir().join(format!("timesheet-summary-test-{}.ron", std::process::id()));
+        std::fs::write(&path, ron::ser::to_string(&entries).unwrap()).unwrap();
+        let start_date = NaiveDate::from_ymd(2022, 7, 1);
+        let time_sheet_summary =
+            TimeSheetSummary::from_file(&path, &start_date, &start_date).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(time_sheet_summary.grand_total(), Duration::hours(1));
+
+        assert!(TimeSheetSummary::from_file(&path, &start_date, &start_date).is_err());
+    }
+
+    #[test]
+    fn test_period_end_date_is_inclusive() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 1);
+        assert_eq!(
+            period_end_date(start_date, 14),
+            NaiveDate::from_ymd(2022, 7, 14)
+        );
+        assert_eq!(period_end_date(start_date, 1), start_date);
+        assert_eq!(
+            date_range(&start_date, &period_end_date(start_date, 14)).len(),
+            14
+        );
+    }
+
+    #[test]
+    fn test_time_sheet_summary_period_bounds() {
+        let start_date = NaiveDate::from_ymd(2022, 7, 1);
+        let end_date = period_end_date(start_date, 14);
+        let entry_on = |day: u32| {
+            TimeSheetEntry::new(
+                "test",
+                datetime_from_ymd_hms(2022, 7, day, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, day, 10, 0, 0),
+                "",
+            )
+        };
+        let entries = vec![entry_on(1), entry_on(14), entry_on(15)];
+        let time_sheet_summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        assert_eq!(
+            time_sheet_summary.dates,
+            vec![start_date, NaiveDate::from_ymd(2022, 7, 14)]
+        );
+        assert_eq!(
+            time_sheet_summary.period_dates.last(),
+            Some(&NaiveDate::from_ymd(2022, 7, 14))
+        );
+        assert_eq!(time_sheet_summary.total_hours_worked(), Duration::hours(2));
     }
 }
@@ -1,8 +1,71 @@
-use crate::TimeSheet::{TimeSheetEntry, TimeSheetSummary};
-use chrono::{Date, DateTime, Duration, NaiveDate, Utc};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::backup::{list_backups, read_backup, resolve_backup_dir, write_backup};
+use crate::export::{
+    csv_with_header, daily_comments_to_csv, entries_from_ron, entries_to_csv,
+    entries_to_csv_by_week, entries_to_ron, export_header, summary_to_html, summary_to_markdown,
+    ReportLabels,
+};
+use crate::import::{csv_header_and_rows, entries_from_csv_rows, parse_csv, CsvColumnMapping};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::pdf::summary_to_pdf;
+use crate::TimeSheet::{
+    add_project_type, copy_day, entries_by_tag, entries_with_uncategorized, expected_period_hours,
+    format_duration_hours, is_date_locked, is_entry_locked, merge_entries, merge_target,
+    month_start, overlapping_entries, parse_manual_minutes, parse_minutes, parse_quick_entry,
+    parse_tags, period_end_date, project_totals, reassign_uncategorized, recent_notes,
+    rename_project, snap_to_quarter_hour, uncategorized_projects, validate_period, week_start_date,
+    weekday_totals, without_locked_entries, PeriodSubmission, RoundingDirection, RoundingSettings,
+    TimeSheetEntry, TimeSheetSummary, DEFAULT_WORKING_DAYS,
+};
+use chrono::{
+    Date, DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Timelike, Utc,
+    Weekday,
+};
+use egui::plot::{Bar, BarChart, Plot};
 use egui::Ui;
 use egui_extras::DatePickerButton;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// Shortcut keys, pressed together with ctrl (cmd on mac)
+const GENERATE_SUMMARY_KEY: egui::Key = egui::Key::G;
+// Starts the selected project's timer, or finishes it if it's already running
+const TOGGLE_TIMER_KEY: egui::Key = egui::Key::T;
+// Opens the manual add form and focuses its minutes field
+const FOCUS_MANUAL_ADD_KEY: egui::Key = egui::Key::M;
+
+// Selects the matching entry of project_types while no text field has focus
+const PROJECT_SELECT_KEYS: [egui::Key; 9] = [
+    egui::Key::Num1,
+    egui::Key::Num2,
+    egui::Key::Num3,
+    egui::Key::Num4,
+    egui::Key::Num5,
+    egui::Key::Num6,
+    egui::Key::Num7,
+    egui::Key::Num8,
+    egui::Key::Num9,
+];
+
+const DEFAULT_SUMMARY_PERIOD_DAYS: i64 = 14;
+
+// Offered in the period length dropdown, any other length can be typed in
+const SUMMARY_PERIOD_PRESETS: [i64; 3] = [7, 14, 30];
+
+// Steps for the manual add minutes buttons
+const MANUAL_MINUTES_STEPS: [i64; 4] = [-15, 15, 30, 60];
+
+// How many recently used notes are offered when filling in notes
+const RECENT_NOTES_LIMIT: usize = 10;
+
+// Timers restored at startup that started longer ago than this offer to finish at the
+// time they were last saved
+const STALE_TIMER_HOURS: i64 = 12;
+
+// How many deletes can be undone
+const UNDO_LIMIT: usize = 10;
+
+// Warn when a period total is off from the expected hours by more than this fraction
+const EXPECTED_HOURS_TOLERANCE: f64 = 0.1;
 
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
@@ -11,29 +74,1140 @@ pub struct TemplateApp {
     time_sheet_entries: Vec<TimeSheetEntry>,
     //This field only exists so we can serialize and deserialize time_sheet_summary_start_date
     time_sheet_start_date: String,
+    //This field only exists so we can serialize and deserialize time_sheet_filters
+    time_sheet_filters: StoredTimeSheetEntryFilters,
+    //This field only exists so we can serialize and deserialize the running timers
+    running_timers: Vec<StoredRunningTimer>,
+    rounding: RoundingSettings,
+    side_panel_width: f32,
+    snap_timer_to_quarter_hour: bool,
+    // Length rounding applied to new entries when they are stored
+    entry_rounding: RoundingSettings,
+    // Otherwise the manual add date goes back to today after each add
+    keep_manual_add_date: bool,
+    period_submissions: HashMap<NaiveDate, PeriodSubmission>,
+    weekly_hours: f64,
+    // Hours to log each day, 0 hides the progress bar
+    daily_goal_hours: f64,
+    week_start: Weekday,
+    // Days are bucketed and dates shown at this offset from UTC
+    utc_offset_minutes: i32,
+    working_days: Vec<Weekday>,
+    auto_pause_enabled: bool,
+    auto_pause_minutes: i64,
+    // Asks what to do with the idle time once there's been no input for this long
+    idle_prompt_enabled: bool,
+    idle_prompt_minutes: i64,
+    // Timers still running at this local time are finished at it
+    daily_cutoff: Option<NaiveTime>,
+    // Finishing a timer that ran longer than this asks whether the time is right
+    long_timer_hours: i64,
+    report_labels: ReportLabels,
+    // Shown at the top of exports after report_labels.name, left out when blank
+    organization: String,
+    color_summary_by_intensity: bool,
+    // Shows each summary cell's notes beneath its hours instead of on hover
+    show_summary_notes_inline: bool,
+    summary_date_format: SummaryDateFormat,
+    grid_density: GridDensity,
+    // Only the timers and today's totals are shown, for keeping the window small
+    compact_mode: bool,
+    duration_display: DurationDisplay,
+    // Name of the unit tracked for a project, e.g. "calls handled"
+    project_unit_labels: HashMap<String, String>,
+    // Projects without a rate aren't billed
+    hourly_rates: HashMap<String, f64>,
+    // Client each project is invoiced to, projects can share one
+    project_clients: HashMap<String, String>,
+    // RGBA, projects without a color get one from their name
+    project_colors: HashMap<String, [u8; 4]>,
+    // Finished projects kept for their entries, they can't be picked for new work
+    archived_projects: HashSet<String>,
+    // Asked for before anything is shown when set
+    pin_lock: Option<PinLock>,
+    //This field only exists so we can serialize and deserialize the PIN lockout
+    pin_lockout: StoredPinLockout,
+    // The entries are copied here on each save, keeping the newest backup_count copies
+    #[cfg(not(target_arch = "wasm32"))]
+    backup_dir: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    backup_count: usize,
+    // Shown in the summary but not counted in its totals or the daily goal. Saves from
+    // before this was added have every project counted, Lunch is only left out of new ones.
+    #[serde(default)]
+    excluded_from_totals: HashSet<String>,
+    summary_period_days: i64,
     #[serde(skip)]
     state: State,
 }
 
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Eq)]
+enum GridDensity {
+    Compact,
+    Comfortable,
+}
+
+impl GridDensity {
+    /// Sets the row and column spacing for grids drawn in `ui`
+    fn apply(&self, ui: &mut Ui) {
+        ui.spacing_mut().item_spacing = match self {
+            GridDensity::Compact => egui::vec2(6.0, 1.0),
+            GridDensity::Comfortable => egui::vec2(10.0, 6.0),
+        };
+    }
+}
+
+/// How the summary grid labels its date columns
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Eq)]
+enum SummaryDateFormat {
+    /// Like 08/09 Tue
+    Date,
+    /// ISO week and weekday, like W32-2 Tue
+    IsoWeek,
+    /// Like 08/09 W32-2
+    Both,
+}
+
+impl SummaryDateFormat {
+    fn column_header(&self, date: &NaiveDate) -> String {
+        let format = match self {
+            SummaryDateFormat::Date => "%m/%d\n%a",
+            SummaryDateFormat::IsoWeek => "W%V-%u\n%a",
+            SummaryDateFormat::Both => "%m/%d\nW%V-%u",
+        };
+        date.format(format).to_string()
+    }
+}
+
+/// The ISO weeks `period_dates` fall in, like "ISO week 32" or "ISO weeks 32-33"
+fn iso_weeks_label(period_dates: &[NaiveDate]) -> Option<String> {
+    let first = period_dates.first()?.iso_week();
+    let last = period_dates.last()?.iso_week();
+    Some(if first == last {
+        format!("ISO week {}", first.week())
+    } else {
+        format!("ISO weeks {}-{}", first.week(), last.week())
+    })
+}
+
+/// How lengths of time are shown in the entries grid
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Eq)]
+enum DurationDisplay {
+    /// Like 1h:30m
+    Clock,
+    /// Like 1.50h, for payroll
+    Decimal,
+}
+
+impl DurationDisplay {
+    fn format(&self, span: &Duration) -> String {
+        match self {
+            DurationDisplay::Clock => format_duration(span),
+            DurationDisplay::Decimal => format!("{}h", format_duration_hours(span)),
+        }
+    }
+}
+
 // Use this to persist anything that we don't want to save between user sessions
 struct State {
     selected_project_type: Option<String>,
     new_project_type: String,
-    work_start_time: Option<DateTime<Utc>>,
-    current_notes: String,
+    new_project_type_error: Option<String>,
+    // Keyed by the project each timer is recording
+    timers: HashMap<String, RunningTimer>,
+    last_active: DateTime<Utc>,
+    // Set while the idle prompt is waiting on an answer, to when the idle time began
+    idle_since: Option<DateTime<Utc>>,
     time_sheet_summary: Option<TimeSheetSummary>,
+    time_sheet_summary_error: Option<String>,
+    // Summarize by tag in place of project
+    summary_by_tag: bool,
+    export_path: String,
+    export_status: Option<String>,
+    data_path: String,
+    config_path: String,
+    csv_import_path: String,
+    csv_import: Option<CsvImport>,
+    // Rows the last CSV import couldn't read
+    csv_import_errors: Vec<String>,
+    config_status: Option<String>,
+    // Run once the frame's UI is done, since they need the whole app
+    config_action: Option<ConfigAction>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pdf_path: String,
+    #[cfg(not(target_arch = "wasm32"))]
+    pdf_status: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    backup_status: Option<String>,
+    // Entries pasted for import, the web version can't read files
+    import_text: String,
+    import_status: Option<String>,
     time_sheet_summary_start_date: Date<Utc>,
     manual_add_project: String,
     manual_add_date: Date<Utc>,
     manual_add_minutes: String,
     manual_add_notes: String,
+    manual_add_units: String,
+    // Comma separated
+    manual_add_tags: String,
+    manual_add_billable: bool,
+    quick_entry: String,
+    quick_entry_error: Option<String>,
+    copy_day_status: Option<String>,
+    // Set when an entry couldn't be duplicated to today
+    duplicate_error: Option<String>,
+    // Where entries for deleted project types are moved to
+    reassign_project: String,
+    // Set when some of the entries couldn't be reassigned
+    reassign_status: Option<String>,
     time_sheet_filters: TimeSheetEntryFilters,
+    // Filters from before "Reset filters" was pressed, so they can be restored
+    previous_filters: Option<TimeSheetEntryFilters>,
+    // Set when the filter dates were picked the wrong way round and swapped
+    filter_dates_swapped: bool,
+    period_approved_by: String,
+    lock_period_on_submit: bool,
+    show_split_preview: bool,
+    focus_manual_add: bool,
+    // Explains timers that were finished at the daily cutoff, until dismissed
+    cutoff_notices: Vec<String>,
+    entry_edit: Option<EntryEdit>,
+    entry_split: Option<EntrySplit>,
+    // First day of the month the calendar shows
+    calendar_month: NaiveDate,
+    // None shows the entries in the order they were added
+    entry_sort: Option<EntrySort>,
+    // The project type being renamed and the name typed for it so far
+    project_rename: Option<(String, String)>,
+    // Waiting on the user to confirm it in the delete window
+    pending_delete: Option<PendingDelete>,
+    // A timer that ran past long_timer_hours and the project to start after it, waiting
+    // on the user to confirm how long it really ran
+    long_finish: Option<(String, Option<String>)>,
+    clear_all_data: Option<ClearAllData>,
+    pin_gate: PinGate,
+    new_pin: String,
+    // Most recent last, never longer than UNDO_LIMIT
+    deleted: Vec<Deleted>,
+}
+
+/// A CSV read in and waiting for its columns to be matched up before it's imported
+struct CsvImport {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+    mapping: CsvColumnMapping,
+}
+
+/// Project types and settings without any entries, so a new install can be set up from
+/// another one
+#[derive(serde::Deserialize, serde::Serialize)]
+struct AppConfig {
+    project_types: Vec<String>,
+    #[serde(default)]
+    hourly_rates: HashMap<String, f64>,
+    #[serde(default)]
+    project_unit_labels: HashMap<String, String>,
+    #[serde(default)]
+    project_colors: HashMap<String, [u8; 4]>,
+    #[serde(default)]
+    project_clients: HashMap<String, String>,
+    #[serde(default)]
+    archived_projects: HashSet<String>,
+    #[serde(default)]
+    excluded_from_totals: HashSet<String>,
+    rounding: RoundingSettings,
+    snap_timer_to_quarter_hour: bool,
+    entry_rounding: RoundingSettings,
+    weekly_hours: f64,
+    daily_goal_hours: f64,
+    week_start: Weekday,
+    working_days: Vec<Weekday>,
+    report_labels: ReportLabels,
+    organization: String,
+}
+
+#[derive(Clone, Copy)]
+enum ConfigAction {
+    Export,
+    Import,
+}
+
+impl TemplateApp {
+    fn config(&self) -> AppConfig {
+        AppConfig {
+            project_types: self.project_types.clone(),
+            hourly_rates: self.hourly_rates.clone(),
+            project_unit_labels: self.project_unit_labels.clone(),
+            project_colors: self.project_colors.clone(),
+            project_clients: self.project_clients.clone(),
+            archived_projects: self.archived_projects.clone(),
+            excluded_from_totals: self.excluded_from_totals.clone(),
+            rounding: self.rounding,
+            snap_timer_to_quarter_hour: self.snap_timer_to_quarter_hour,
+            entry_rounding: self.entry_rounding,
+            weekly_hours: self.weekly_hours,
+            daily_goal_hours: self.daily_goal_hours,
+            week_start: self.week_start,
+            working_days: self.working_days.clone(),
+            report_labels: self.report_labels.clone(),
+            organization: self.organization.clone(),
+        }
+    }
+
+    /// Adds the config's project types that are missing here, replaces the rates and unit
+    /// labels of the projects it has them for, and replaces the other settings
+    fn apply_config(&mut self, config: AppConfig) {
+        for project_type in config.project_types {
+            if !self.project_types.contains(&project_type) {
+                self.project_types.push(project_type);
+            }
+        }
+        self.hourly_rates.extend(config.hourly_rates);
+        self.project_unit_labels.extend(config.project_unit_labels);
+        self.project_colors.extend(config.project_colors);
+        self.project_clients.extend(config.project_clients);
+        self.archived_projects.extend(config.archived_projects);
+        self.excluded_from_totals
+            .extend(config.excluded_from_totals);
+        self.rounding = config.rounding;
+        self.snap_timer_to_quarter_hour = config.snap_timer_to_quarter_hour;
+        self.entry_rounding = config.entry_rounding;
+        self.weekly_hours = config.weekly_hours;
+        self.daily_goal_hours = config.daily_goal_hours;
+        self.week_start = config.week_start;
+        self.working_days = config.working_days;
+        self.report_labels = config.report_labels;
+        self.organization = config.organization;
+    }
+
+    fn run_config_action(&mut self, ctx: &egui::Context, action: ConfigAction) {
+        let status = match action {
+            ConfigAction::Export => {
+                match ron::ser::to_string_pretty(&self.config(), ron::ser::PrettyConfig::default())
+                {
+                    Ok(contents) => save_export(ctx, &self.state.config_path, contents),
+                    Err(error) => format!("Couldn't write the config: {}", error),
+                }
+            }
+            ConfigAction::Import => {
+                match read_import(&self.state.config_path, &self.state.import_text).and_then(
+                    |contents| {
+                        ron::from_str::<AppConfig>(&contents)
+                            .map_err(|error| format!("Couldn't read the config: {}", error))
+                    },
+                ) {
+                    Ok(config) => {
+                        self.apply_config(config);
+                        "Imported the config".to_string()
+                    }
+                    Err(error) => error,
+                }
+            }
+        };
+        self.state.config_status = Some(status);
+    }
+}
+
+/// A PIN asked for before any data is shown. Only a salted hash of it is kept, and the
+/// saved data itself isn't encrypted.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+struct PinLock {
+    salt: u64,
+    hash: u64,
+}
+
+impl PinLock {
+    fn new(pin: &str, salt: u64) -> Self {
+        Self {
+            salt,
+            hash: pin_hash(salt, pin.trim()),
+        }
+    }
+
+    fn matches(&self, pin: &str) -> bool {
+        pin_hash(self.salt, pin.trim()) == self.hash
+    }
+}
+
+fn pin_hash(salt: u64, pin: &str) -> u64 {
+    stable_hash(salt.to_le_bytes().iter().chain(pin.as_bytes()))
+}
+
+/// FNV-1a, which unlike the std hashers is sure to give the same value on every Rust
+/// version, so a saved PIN keeps working and a project keeps its picked color
+fn stable_hash<'a>(bytes: impl Iterator<Item = &'a u8>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+const PIN_ATTEMPTS: u32 = 5;
+const PIN_LOCKOUT_SECONDS: i64 = 60;
+const PIN_MIN_LENGTH: usize = 4;
+
+/// The PIN entry shown at startup, locking out for a while after too many wrong PINs
+#[derive(Default)]
+struct PinGate {
+    entry: String,
+    unlocked: bool,
+    failed_attempts: u32,
+    locked_until: Option<DateTime<Utc>>,
+    error: Option<String>,
+}
+
+impl PinGate {
+    fn try_unlock(&mut self, lock: &PinLock, now: DateTime<Utc>) {
+        let entry = std::mem::take(&mut self.entry);
+        if let Some(locked_until) = self.locked_until {
+            if now < locked_until {
+                self.error = Some(format!(
+                    "Too many wrong PINs, try again in {} seconds",
+                    (locked_until - now).num_seconds() + 1
+                ));
+                return;
+            }
+            self.locked_until = None;
+        }
+        if lock.matches(&entry) {
+            self.unlocked = true;
+            self.failed_attempts = 0;
+            self.error = None;
+            return;
+        }
+        self.failed_attempts += 1;
+        if self.failed_attempts >= PIN_ATTEMPTS {
+            self.failed_attempts = 0;
+            self.locked_until = Some(now + Duration::seconds(PIN_LOCKOUT_SECONDS));
+            self.error = Some(format!(
+                "Too many wrong PINs, try again in {} seconds",
+                PIN_LOCKOUT_SECONDS
+            ));
+        } else {
+            self.error = Some(format!(
+                "Wrong PIN, {} tries left",
+                PIN_ATTEMPTS - self.failed_attempts
+            ));
+        }
+    }
+}
+
+// Kept so restarting the app doesn't reset a PIN lockout. Times are stored as rfc3339
+// strings like time_sheet_start_date, an empty locked_until means not locked out.
+#[derive(serde::Deserialize, serde::Serialize, Default)]
+#[serde(default)]
+struct StoredPinLockout {
+    failed_attempts: u32,
+    locked_until: String,
+}
+
+impl From<&PinGate> for StoredPinLockout {
+    fn from(pin_gate: &PinGate) -> Self {
+        Self {
+            failed_attempts: pin_gate.failed_attempts,
+            locked_until: pin_gate
+                .locked_until
+                .map(|locked_until| locked_until.to_rfc3339())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl StoredPinLockout {
+    fn restore(&self) -> PinGate {
+        PinGate {
+            failed_attempts: self.failed_attempts,
+            locked_until: parse_stored_datetime(&self.locked_until),
+            ..PinGate::default()
+        }
+    }
+}
+
+/// The clear all data confirmation. It takes continuing past a warning, then typing
+/// CLEAR_ALL_DATA_CONFIRMATION.
+#[derive(Default)]
+struct ClearAllData {
+    clear_project_types: bool,
+    warned: bool,
+    typed_confirmation: String,
+}
+
+const CLEAR_ALL_DATA_CONFIRMATION: &str = "DELETE";
+
+/// Something removed by a delete, with the position it was removed from
+enum Deleted {
+    Entry(usize, TimeSheetEntry),
+    ProjectType(usize, String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EntrySortColumn {
+    Project,
+    Start,
+    End,
+    Elapsed,
+}
+
+#[derive(Clone, Copy)]
+struct EntrySort {
+    column: EntrySortColumn,
+    ascending: bool,
+}
+
+/// Indices into `entries` in the order the grid shows them, `entries` itself is never
+/// reordered so the indices stay valid for edits and deletes
+fn sorted_entry_indices(entries: &[TimeSheetEntry], sort: Option<EntrySort>) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..entries.len()).collect();
+    if let Some(sort) = sort {
+        indices.sort_by(|a, b| {
+            let (a, b) = (&entries[*a], &entries[*b]);
+            let ordering = match sort.column {
+                EntrySortColumn::Project => a.project_type.cmp(&b.project_type),
+                EntrySortColumn::Start => a.work_start_datetime.cmp(&b.work_start_datetime),
+                EntrySortColumn::End => a.work_end_datetime.cmp(&b.work_end_datetime),
+                EntrySortColumn::Elapsed => (a.work_end_datetime - a.work_start_datetime)
+                    .cmp(&(b.work_end_datetime - b.work_start_datetime)),
+            };
+            if sort.ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+    }
+    indices
+}
+
+/// A button clicked on a row of the entries grid, with the index of the row's entry
+#[derive(Clone, Copy)]
+enum EntryAction {
+    Edit(usize),
+    Split(usize),
+    Duplicate(usize),
+    Delete(usize),
+}
+
+#[derive(Clone, Copy)]
+enum PendingDelete {
+    // The entry's created_at is checked before deleting, in case the entries have moved
+    // since it was picked
+    Entry(usize, DateTime<Utc>),
+    ProjectType(usize),
+}
+
+/// A timer recording work on one project
+struct RunningTimer {
+    started_at: DateTime<Utc>,
+    // When each finished pause began and ended, not counting a pause still in progress
+    pauses: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    paused_at: Option<DateTime<Utc>>,
+    // Set by the Pause button, these pauses don't resume on input like idle pauses do
+    paused_manually: bool,
+    notes: String,
+    // Project to switch to with "Finish and start next"
+    next_project_type: Option<String>,
+    // When a timer restored at startup had been running a long time this is when the
+    // app last saved it, which is likely when the work stopped
+    stale_since: Option<DateTime<Utc>>,
+    // The local time typed in to finish the timer at, while that's being picked
+    finish_at: Option<String>,
+}
+
+impl RunningTimer {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            started_at: now,
+            pauses: Vec::new(),
+            paused_at: None,
+            paused_manually: false,
+            notes: String::new(),
+            next_project_type: None,
+            stale_since: None,
+            finish_at: None,
+        }
+    }
+
+    fn pause(&mut self, paused_at: DateTime<Utc>, manually: bool) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(paused_at);
+            self.paused_manually = manually;
+        }
+    }
+
+    fn resume(&mut self, now: DateTime<Utc>) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.pauses.push((paused_at, now));
+        }
+        self.paused_manually = false;
+    }
+
+    /// Leaves the time from `since` until `now` out of the timer, as if it had been paused.
+    /// A timer paused with the Pause button stays paused, from `since` if that's earlier.
+    fn discard_since(&mut self, since: DateTime<Utc>, now: DateTime<Utc>) {
+        let since = since.max(self.started_at);
+        if self.paused_manually {
+            self.paused_at = self.paused_at.map(|paused_at| paused_at.min(since));
+            return;
+        }
+        self.pause(since, false);
+        self.resume(now);
+    }
+
+    /// Time worked on the timer up to `now`, leaving out the paused time before then
+    fn elapsed(&self, now: DateTime<Utc>) -> Duration {
+        let paused: Duration = self
+            .pauses
+            .iter()
+            .chain(
+                self.paused_at
+                    .as_ref()
+                    .map(|paused_at| (*paused_at, now))
+                    .iter(),
+            )
+            .map(|(paused_at, resumed_at)| {
+                ((*resumed_at).min(now) - *paused_at).max(Duration::zero())
+            })
+            .fold(Duration::zero(), |total, pause| total + pause);
+        now - self.started_at - paused
+    }
+
+    /// True when the timer has run longer than `hours` and hasn't already been flagged as
+    /// left running while the app was closed
+    fn runs_longer_than(&self, now: DateTime<Utc>, hours: i64) -> bool {
+        self.stale_since.is_none() && self.elapsed(now) > Duration::hours(hours)
+    }
+
+    /// The first time after the timer started that the clock in `utc_offset` reads `cutoff`
+    fn next_cutoff(&self, cutoff: NaiveTime, utc_offset: &FixedOffset) -> DateTime<Utc> {
+        let started_at = self.started_at.with_timezone(utc_offset).naive_local();
+        let mut cutoff_at = started_at.date().and_time(cutoff);
+        if cutoff_at <= started_at {
+            cutoff_at += Duration::days(1);
+        }
+        utc_offset
+            .from_local_datetime(&cutoff_at)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+}
+
+impl State {
+    /// Starts a timer for `project_type`, unless one is already running for it
+    fn start_timer(&mut self, project_type: &str, now: DateTime<Utc>) {
+        self.timers
+            .entry(project_type.to_string())
+            .or_insert_with(|| RunningTimer::new(now));
+    }
+
+    /// Stops the timer for `project_type` and returns the entry for the recorded work
+    fn finish_timer(
+        &mut self,
+        project_type: &str,
+        now: DateTime<Utc>,
+        snap_timer_to_quarter_hour: bool,
+        entry_rounding: &RoundingSettings,
+    ) -> Option<TimeSheetEntry> {
+        let timer = self.timers.remove(project_type)?;
+        let work_end_datetime = timer.started_at
+            + stored_duration(
+                timer.elapsed(now),
+                snap_timer_to_quarter_hour,
+                entry_rounding,
+            );
+        Some(TimeSheetEntry::new(
+            project_type,
+            timer.started_at,
+            work_end_datetime,
+            &timer.notes,
+        ))
+    }
+
+    fn generate_time_sheet_summary(
+        &mut self,
+        time_sheet_entries: &[TimeSheetEntry],
+        summary_period_days: i64,
+        utc_offset: &FixedOffset,
+        excluded_from_totals: &HashSet<String>,
+        project_types: &[String],
+    ) {
+        let start_date = self.time_sheet_summary_start_date.naive_utc();
+        let end_date = period_end_date(start_date, summary_period_days);
+        match validate_period(&start_date, &end_date) {
+            Ok((start_date, end_date)) => {
+                let tagged_entries;
+                let time_sheet_entries = if self.summary_by_tag {
+                    tagged_entries = entries_by_tag(time_sheet_entries);
+                    &tagged_entries
+                } else if !uncategorized_projects(time_sheet_entries, project_types).is_empty() {
+                    tagged_entries = entries_with_uncategorized(time_sheet_entries, project_types);
+                    &tagged_entries
+                } else {
+                    time_sheet_entries
+                };
+                let mut time_sheet_summary = TimeSheetSummary::new_in(
+                    time_sheet_entries,
+                    &start_date,
+                    &end_date,
+                    utc_offset,
+                );
+                time_sheet_summary.excluded_from_totals = excluded_from_totals.clone();
+                self.time_sheet_summary = Some(time_sheet_summary);
+                self.time_sheet_summary_error = None;
+            }
+            Err(error) => {
+                self.time_sheet_summary = None;
+                self.time_sheet_summary_error = Some(error);
+            }
+        }
+    }
+}
+
+/// Common date ranges for the entry filters
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum QuickDateRange {
+    Today,
+    ThisWeek,
+    LastWeek,
+    ThisMonth,
+    PayPeriod,
+}
+
+impl QuickDateRange {
+    const ALL: [QuickDateRange; 5] = [
+        QuickDateRange::Today,
+        QuickDateRange::ThisWeek,
+        QuickDateRange::LastWeek,
+        QuickDateRange::ThisMonth,
+        QuickDateRange::PayPeriod,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            QuickDateRange::Today => "Today",
+            QuickDateRange::ThisWeek => "This Week",
+            QuickDateRange::LastWeek => "Last Week",
+            QuickDateRange::ThisMonth => "This Month",
+            QuickDateRange::PayPeriod => "This Pay Period",
+        }
+    }
+
+    /// The first and last dates of the range containing `today`. Pay periods repeat every
+    /// `period_days` from the summary's `period_start`, in either direction.
+    fn dates(
+        &self,
+        today: NaiveDate,
+        week_start: Weekday,
+        period_start: NaiveDate,
+        period_days: i64,
+    ) -> (NaiveDate, NaiveDate) {
+        match self {
+            QuickDateRange::Today => (today, today),
+            QuickDateRange::ThisWeek => {
+                let start = week_start_date(today, week_start);
+                (start, start + Duration::days(6))
+            }
+            QuickDateRange::LastWeek => {
+                let start = week_start_date(today, week_start) - Duration::days(7);
+                (start, start + Duration::days(6))
+            }
+            QuickDateRange::ThisMonth => (month_start(today, 0), month_start(today, 1).pred()),
+            QuickDateRange::PayPeriod => {
+                let period_days = period_days.max(1);
+                let periods = (today - period_start).num_days().div_euclid(period_days);
+                let start = period_start + Duration::days(periods * period_days);
+                (start, period_end_date(start, period_days))
+            }
+        }
+    }
 }
 
 struct TimeSheetEntryFilters {
+    project_type: String,
+    // Local dates, at the utc_offset the entries are matched at
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    without_notes_only: bool,
+    // Matched anywhere in the notes, ignoring case
+    notes_search: String,
+    // Matches entries with this exact tag, ignoring case
+    tag: String,
+}
+
+impl Default for TimeSheetEntryFilters {
+    fn default() -> Self {
+        let today = local_today(&utc_offset_from_minutes(local_utc_offset_minutes())).naive_utc();
+        Self {
+            project_type: String::new(),
+            start_date: today - Duration::days(365),
+            end_date: today + Duration::days(365),
+            without_notes_only: false,
+            notes_search: String::new(),
+            tag: String::new(),
+        }
+    }
+}
+
+// Dates are stored as rfc3339 strings like time_sheet_start_date
+#[derive(serde::Deserialize, serde::Serialize, Default)]
+#[serde(default)]
+struct StoredTimeSheetEntryFilters {
+    project_type: String,
+    start_date: String,
+    end_date: String,
+    without_notes_only: bool,
+    notes_search: String,
+    tag: String,
+}
+
+impl From<&TimeSheetEntryFilters> for StoredTimeSheetEntryFilters {
+    fn from(filters: &TimeSheetEntryFilters) -> Self {
+        Self {
+            project_type: filters.project_type.to_string(),
+            start_date: Utc
+                .from_utc_date(&filters.start_date)
+                .and_hms(0, 0, 0)
+                .to_rfc3339(),
+            end_date: Utc
+                .from_utc_date(&filters.end_date)
+                .and_hms(0, 0, 0)
+                .to_rfc3339(),
+            without_notes_only: filters.without_notes_only,
+            notes_search: filters.notes_search.to_string(),
+            tag: filters.tag.to_string(),
+        }
+    }
+}
+
+impl StoredTimeSheetEntryFilters {
+    fn restore(&self) -> TimeSheetEntryFilters {
+        let defaults = TimeSheetEntryFilters::default();
+        TimeSheetEntryFilters {
+            project_type: self.project_type.to_string(),
+            start_date: parse_stored_date(&self.start_date)
+                .map_or(defaults.start_date, |date| date.naive_utc()),
+            end_date: parse_stored_date(&self.end_date)
+                .map_or(defaults.end_date, |date| date.naive_utc()),
+            without_notes_only: self.without_notes_only,
+            notes_search: self.notes_search.to_string(),
+            tag: self.tag.to_string(),
+        }
+    }
+}
+
+fn parse_stored_date(value: &str) -> Option<Date<Utc>> {
+    parse_stored_datetime(value).map(|result| result.date())
+}
+
+fn parse_stored_datetime(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|result| result.with_timezone(&Utc))
+}
+
+// Times are stored as rfc3339 strings like time_sheet_start_date, an empty paused_at
+// means the timer wasn't paused
+#[derive(serde::Deserialize, serde::Serialize, Default)]
+#[serde(default)]
+struct StoredRunningTimer {
+    project_type: String,
+    started_at: String,
+    // Only written by older versions, which kept the total instead of each pause
+    paused_total_seconds: i64,
+    pauses: Vec<(String, String)>,
+    paused_at: String,
+    paused_manually: bool,
+    notes: String,
+    saved_at: String,
+}
+
+impl StoredRunningTimer {
+    fn new(project_type: &str, timer: &RunningTimer, saved_at: DateTime<Utc>) -> Self {
+        Self {
+            project_type: project_type.to_string(),
+            started_at: timer.started_at.to_rfc3339(),
+            paused_total_seconds: 0,
+            pauses: timer
+                .pauses
+                .iter()
+                .map(|(paused_at, resumed_at)| (paused_at.to_rfc3339(), resumed_at.to_rfc3339()))
+                .collect(),
+            paused_at: timer
+                .paused_at
+                .map(|paused_at| paused_at.to_rfc3339())
+                .unwrap_or_default(),
+            paused_manually: timer.paused_manually,
+            notes: timer.notes.to_string(),
+            saved_at: saved_at.to_rfc3339(),
+        }
+    }
+
+    /// The timer to keep running, or None if it can't be read back
+    fn restore(&self, now: DateTime<Utc>) -> Option<RunningTimer> {
+        let started_at = parse_stored_datetime(&self.started_at)?;
+        let saved_at = parse_stored_datetime(&self.saved_at).unwrap_or(now);
+        let stale_since = if now - started_at > Duration::hours(STALE_TIMER_HOURS) {
+            Some(saved_at.max(started_at))
+        } else {
+            None
+        };
+        let mut pauses: Vec<(DateTime<Utc>, DateTime<Utc>)> = self
+            .pauses
+            .iter()
+            .filter_map(|(paused_at, resumed_at)| {
+                Some((
+                    parse_stored_datetime(paused_at)?,
+                    parse_stored_datetime(resumed_at)?,
+                ))
+            })
+            .collect();
+        // When the older total paused time was taken isn't known, so it counts from the start
+        if self.paused_total_seconds > 0 {
+            pauses.push((
+                started_at,
+                started_at + Duration::seconds(self.paused_total_seconds),
+            ));
+        }
+        Some(RunningTimer {
+            started_at,
+            pauses,
+            paused_at: parse_stored_datetime(&self.paused_at),
+            paused_manually: self.paused_manually,
+            notes: self.notes.to_string(),
+            next_project_type: None,
+            stale_since,
+            finish_at: None,
+        })
+    }
+}
+
+impl TimeSheetEntryFilters {
+    /// The dates are compared with the entry's days at `utc_offset`, the days the grid
+    /// and the calendar show it under
+    fn matches(&self, entry: &TimeSheetEntry, utc_offset: &FixedOffset) -> bool {
+        if !self.project_type.is_empty()
+            && !entry
+                .project_type
+                .to_lowercase()
+                .contains(&self.project_type.to_lowercase())
+        {
+            return false;
+        }
+        if (self.start_date
+            > entry
+                .work_start_datetime
+                .with_timezone(utc_offset)
+                .date_naive())
+            || (self.end_date
+                < entry
+                    .work_end_datetime
+                    .with_timezone(utc_offset)
+                    .date_naive())
+        {
+            return false;
+        }
+        if self.without_notes_only && !entry.notes.trim().is_empty() {
+            return false;
+        }
+        if !self.notes_search.is_empty()
+            && !entry
+                .notes
+                .to_lowercase()
+                .contains(&self.notes_search.to_lowercase())
+        {
+            return false;
+        }
+        if !self.tag.trim().is_empty() && !entry.has_tag(self.tag.trim()) {
+            return false;
+        }
+        true
+    }
+}
+
+/// The fields of an entry being edited in the entries grid. Dates and times are shown
+/// at `utc_offset` like the grid.
+struct EntryEdit {
+    index: usize,
+    utc_offset: FixedOffset,
+    // The times being edited, so ones left alone keep their seconds
+    original_start: DateTime<Utc>,
+    original_end: DateTime<Utc>,
     project_type: String,
     start_date: Date<Utc>,
+    start_time: String,
     end_date: Date<Utc>,
+    end_time: String,
+    notes: String,
+    // Comma separated
+    tags: String,
+    billable: bool,
+    error: Option<String>,
+}
+
+impl EntryEdit {
+    fn new(index: usize, entry: &TimeSheetEntry, utc_offset: &FixedOffset) -> Self {
+        let (start_date, start_time) = edit_date_and_time(&entry.work_start_datetime, utc_offset);
+        let (end_date, end_time) = edit_date_and_time(&entry.work_end_datetime, utc_offset);
+        Self {
+            index,
+            utc_offset: *utc_offset,
+            original_start: entry.work_start_datetime,
+            original_end: entry.work_end_datetime,
+            project_type: entry.project_type.clone(),
+            start_date,
+            start_time,
+            end_date,
+            end_time,
+            notes: entry.notes.clone(),
+            tags: entry.tags.join(", "),
+            billable: entry.billable,
+            error: None,
+        }
+    }
+
+    /// Writes the edited fields into `entry`, or explains why they can't be saved
+    fn apply(&self, entry: &mut TimeSheetEntry) -> Result<(), String> {
+        let work_start_datetime = keep_seconds(
+            parse_edit_datetime(&self.start_date, &self.start_time, &self.utc_offset)?,
+            self.original_start,
+        );
+        let work_end_datetime = keep_seconds(
+            parse_edit_datetime(&self.end_date, &self.end_time, &self.utc_offset)?,
+            self.original_end,
+        );
+        if work_end_datetime < work_start_datetime {
+            return Err("the end time is before the start time".to_string());
+        }
+        entry.project_type = self.project_type.clone();
+        entry.work_start_datetime = work_start_datetime;
+        entry.work_end_datetime = work_end_datetime;
+        entry.notes = self.notes.clone();
+        entry.tags = parse_tags(&self.tags);
+        entry.billable = self.billable;
+        entry.touch();
+        Ok(())
+    }
+}
+
+/// The form for splitting the entry at `index` in two, with the part after the split
+/// time going to `project_type`
+struct EntrySplit {
+    index: usize,
+    utc_offset: FixedOffset,
+    // Where the form started out, kept to the second if the time isn't changed
+    midpoint: DateTime<Utc>,
+    date: Date<Utc>,
+    time: String,
+    project_type: String,
+    error: Option<String>,
+}
+
+impl EntrySplit {
+    fn new(index: usize, entry: &TimeSheetEntry, utc_offset: &FixedOffset) -> Self {
+        let midpoint =
+            entry.work_start_datetime + (entry.work_end_datetime - entry.work_start_datetime) / 2;
+        let (date, time) = edit_date_and_time(&midpoint, utc_offset);
+        Self {
+            index,
+            utc_offset: *utc_offset,
+            midpoint,
+            date,
+            time,
+            project_type: entry.project_type.clone(),
+            error: None,
+        }
+    }
+
+    /// Replaces the entry with its two parts, or explains why it can't be split there
+    fn apply(&self, entries: &mut Vec<TimeSheetEntry>) -> Result<(), String> {
+        let at = keep_seconds(
+            parse_edit_datetime(&self.date, &self.time, &self.utc_offset)?,
+            self.midpoint,
+        );
+        let entry = entries
+            .get(self.index)
+            .ok_or_else(|| "the entry was removed".to_string())?;
+        let (first, second) = entry.split_at(at, &self.project_type)?;
+        entries[self.index] = first;
+        entries.insert(self.index + 1, second);
+        Ok(())
+    }
+}
+
+/// The last moment up to `now` that the clock in `utc_offset` read `time`, for finishing a
+/// timer after the work stopped. It has to be after the timer started at `started_at`.
+fn retroactive_end(
+    started_at: DateTime<Utc>,
+    time: &str,
+    now: DateTime<Utc>,
+    utc_offset: &FixedOffset,
+) -> Result<DateTime<Utc>, String> {
+    let time = NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| format!("\"{}\" isn't a time like 13:45", time))?;
+    let now_local = now.with_timezone(utc_offset).naive_local();
+    let mut end_local = now_local.date().and_time(time);
+    if end_local > now_local {
+        end_local -= Duration::days(1);
+    }
+    let end = utc_offset
+        .from_local_datetime(&end_local)
+        .unwrap()
+        .with_timezone(&Utc);
+    if end <= started_at {
+        return Err(format!(
+            "the end has to be after the timer started at {}",
+            started_at.with_timezone(utc_offset).format("%F %R")
+        ));
+    }
+    Ok(end)
+}
+
+/// `minutes` moved by `step`, kept inside the day. Minutes that can't be read count as 0.
+fn step_minutes(minutes: &str, step: i64) -> String {
+    let minutes = parse_minutes(minutes).map_or(0, |minutes| minutes.round() as i64);
+    (minutes + step).clamp(0, 24 * 60 - 1).to_string()
+}
+
+/// The date and time of `datetime` at `utc_offset` for the edit forms. The date is held
+/// as a `Date<Utc>` for the date picker.
+fn edit_date_and_time(datetime: &DateTime<Utc>, utc_offset: &FixedOffset) -> (Date<Utc>, String) {
+    let local = datetime.with_timezone(utc_offset);
+    (
+        Utc.from_utc_date(&local.date_naive()),
+        local.format("%H:%M").to_string(),
+    )
+}
+
+/// Reads a date and time typed at `utc_offset`
+fn parse_edit_datetime(
+    date: &Date<Utc>,
+    time: &str,
+    utc_offset: &FixedOffset,
+) -> Result<DateTime<Utc>, String> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .ok()
+        .and_then(|time| {
+            utc_offset
+                .from_local_datetime(&date.naive_utc().and_time(time))
+                .single()
+        })
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .ok_or_else(|| format!("\"{}\" isn't a time like 13:45", time))
+}
+
+/// The forms only show minutes, so a time still on the minute of `original` is taken to
+/// be unchanged and keeps its seconds
+fn keep_seconds(edited: DateTime<Utc>, original: DateTime<Utc>) -> DateTime<Utc> {
+    if original
+        .with_second(0)
+        .and_then(|datetime| datetime.with_nanosecond(0))
+        == Some(edited)
+    {
+        original
+    } else {
+        edited
+    }
 }
 
 impl Default for TemplateApp {
@@ -43,22 +1217,106 @@ impl Default for TemplateApp {
             project_types: vec!["Lunch".to_string(), "Meetings".to_string()],
             time_sheet_entries: Vec::new(),
             time_sheet_start_date: String::new(),
+            time_sheet_filters: StoredTimeSheetEntryFilters::default(),
+            running_timers: Vec::new(),
+            rounding: RoundingSettings::default(),
+            side_panel_width: 200.0,
+            snap_timer_to_quarter_hour: false,
+            entry_rounding: RoundingSettings::default(),
+            keep_manual_add_date: true,
+            period_submissions: HashMap::new(),
+            weekly_hours: 40.0,
+            daily_goal_hours: 8.0,
+            week_start: Weekday::Mon,
+            utc_offset_minutes: local_utc_offset_minutes(),
+            working_days: DEFAULT_WORKING_DAYS.to_vec(),
+            auto_pause_enabled: false,
+            auto_pause_minutes: 10,
+            idle_prompt_enabled: true,
+            idle_prompt_minutes: 15,
+            daily_cutoff: None,
+            long_timer_hours: 12,
+            report_labels: ReportLabels::default(),
+            organization: String::new(),
+            color_summary_by_intensity: false,
+            show_summary_notes_inline: false,
+            summary_date_format: SummaryDateFormat::Date,
+            grid_density: GridDensity::Comfortable,
+            compact_mode: false,
+            duration_display: DurationDisplay::Clock,
+            project_unit_labels: HashMap::new(),
+            hourly_rates: HashMap::new(),
+            project_clients: HashMap::new(),
+            project_colors: HashMap::new(),
+            archived_projects: HashSet::new(),
+            pin_lock: None,
+            pin_lockout: StoredPinLockout::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            backup_dir: "backups".to_string(),
+            #[cfg(not(target_arch = "wasm32"))]
+            backup_count: 10,
+            excluded_from_totals: HashSet::from(["Lunch".to_string()]),
+            summary_period_days: DEFAULT_SUMMARY_PERIOD_DAYS,
             state: State {
                 selected_project_type: None,
                 new_project_type: String::new().to_owned(),
-                work_start_time: None,
-                current_notes: String::new().to_owned(),
+                new_project_type_error: None,
+                timers: HashMap::new(),
+                last_active: chrono::offset::Utc::now(),
+                idle_since: None,
                 time_sheet_summary: None,
+                time_sheet_summary_error: None,
+                summary_by_tag: false,
+                export_path: "timesheet.csv".to_string(),
+                #[cfg(not(target_arch = "wasm32"))]
+                pdf_path: "timesheet.pdf".to_string(),
+                #[cfg(not(target_arch = "wasm32"))]
+                pdf_status: None,
+                #[cfg(not(target_arch = "wasm32"))]
+                backup_status: None,
+                export_status: None,
+                data_path: "timesheet.ron".to_string(),
+                config_path: "timesheet-config.ron".to_string(),
+                csv_import_path: "import.csv".to_string(),
+                csv_import: None,
+                csv_import_errors: Vec::new(),
+                config_status: None,
+                config_action: None,
+                import_text: String::new(),
+                import_status: None,
                 time_sheet_summary_start_date: chrono::offset::Utc::today(),
-                manual_add_date: chrono::offset::Utc::today(),
+                manual_add_date: local_today(&utc_offset_from_minutes(local_utc_offset_minutes())),
                 manual_add_notes: String::new().to_owned(),
+                manual_add_units: String::new(),
+                manual_add_tags: String::new(),
+                manual_add_billable: true,
+                quick_entry: String::new(),
+                quick_entry_error: None,
+                copy_day_status: None,
+                duplicate_error: None,
+                reassign_project: String::new(),
+                reassign_status: None,
                 manual_add_minutes: String::new().to_owned(),
                 manual_add_project: String::new().to_owned(),
-                time_sheet_filters: TimeSheetEntryFilters {
-                    project_type: String::new(),
-                    start_date: chrono::offset::Utc::today() - Duration::days(365),
-                    end_date: chrono::offset::Utc::today() + Duration::days(365),
-                },
+                time_sheet_filters: TimeSheetEntryFilters::default(),
+                previous_filters: None,
+                filter_dates_swapped: false,
+                period_approved_by: String::new(),
+                lock_period_on_submit: true,
+                show_split_preview: false,
+                focus_manual_add: false,
+                cutoff_notices: Vec::new(),
+                entry_edit: None,
+                entry_split: None,
+                calendar_month: month_start(chrono::offset::Utc::today().naive_utc(), 0),
+                entry_sort: None,
+                project_rename: None,
+                pending_delete: None,
+                long_finish: None,
+                clear_all_data: None,
+                pin_gate: PinGate::default(),
+                new_pin: String::new(),
+                deleted: Vec::new(),
             },
         }
     }
@@ -75,13 +1333,23 @@ impl TemplateApp {
         if let Some(storage) = cc.storage {
             let mut stored_state: TemplateApp =
                 eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
-            let deserialized_end_date =
-                match DateTime::parse_from_rfc3339(&stored_state.time_sheet_start_date) {
-                    Ok(result) => result.with_timezone(&Utc).date(),
-                    Err(..) => chrono::offset::Utc::today(),
-                };
+            let deserialized_end_date = parse_stored_date(&stored_state.time_sheet_start_date)
+                .unwrap_or_else(chrono::offset::Utc::today);
 
             stored_state.state.time_sheet_summary_start_date = deserialized_end_date;
+            stored_state.state.manual_add_date =
+                local_today(&utc_offset_from_minutes(stored_state.utc_offset_minutes));
+            stored_state.state.time_sheet_filters = stored_state.time_sheet_filters.restore();
+            stored_state.state.pin_gate = stored_state.pin_lockout.restore();
+            let now = chrono::offset::Utc::now();
+            for stored_timer in stored_state.running_timers.iter() {
+                if let Some(timer) = stored_timer.restore(now) {
+                    stored_state
+                        .state
+                        .timers
+                        .insert(stored_timer.project_type.to_string(), timer);
+                }
+            }
             return stored_state;
         }
 
@@ -97,6 +1365,30 @@ impl eframe::App for TemplateApp {
             .time_sheet_summary_start_date
             .and_hms(0, 0, 0)
             .to_rfc3339();
+        self.time_sheet_filters = (&self.state.time_sheet_filters).into();
+        self.pin_lockout = (&self.state.pin_gate).into();
+        let now = chrono::offset::Utc::now();
+        self.running_timers = self
+            .state
+            .timers
+            .iter()
+            .map(|(project_type, timer)| StoredRunningTimer::new(project_type, timer, now))
+            .collect();
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.backup_dir.trim().is_empty() {
+            match write_backup(
+                &resolve_backup_dir(&self.backup_dir),
+                &self.time_sheet_entries,
+                now,
+                self.backup_count,
+            ) {
+                Ok(Some(path)) => {
+                    self.state.backup_status = Some(format!("Backed up to {}", path.display()))
+                }
+                Ok(None) => {}
+                Err(error) => self.state.backup_status = Some(error),
+            }
+        }
         eframe::set_value(storage, eframe::APP_KEY, self);
     }
 
@@ -107,7 +1399,7 @@ impl eframe::App for TemplateApp {
     #[cfg(not(target_arch = "wasm32"))] // no File->Quit on web pages!
     fn on_close_event(&mut self) -> bool {
         // Don't close when we're actively recording time
-        self.state.work_start_time.is_none()
+        self.state.timers.is_empty()
     }
 
     #[cfg(not(target_arch = "wasm32"))] // no File->Quit on web pages!
@@ -123,170 +1415,1837 @@ impl eframe::App for TemplateApp {
 
     /// Called each time the UI needs repainting, which may be many times per second.
     /// Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         let Self {
             time_sheet_entries,
             project_types,
             state,
             time_sheet_start_date: _,
+            time_sheet_filters: _,
+            running_timers: _,
+            rounding,
+            side_panel_width,
+            snap_timer_to_quarter_hour,
+            entry_rounding,
+            keep_manual_add_date,
+            period_submissions,
+            weekly_hours,
+            daily_goal_hours,
+            week_start,
+            utc_offset_minutes,
+            working_days,
+            auto_pause_enabled,
+            auto_pause_minutes,
+            idle_prompt_enabled,
+            idle_prompt_minutes,
+            daily_cutoff,
+            long_timer_hours,
+            report_labels,
+            organization,
+            color_summary_by_intensity,
+            show_summary_notes_inline,
+            summary_date_format,
+            grid_density,
+            compact_mode,
+            duration_display,
+            project_unit_labels,
+            hourly_rates,
+            project_clients,
+            project_colors,
+            archived_projects,
+            pin_lock,
+            pin_lockout: _,
+            #[cfg(not(target_arch = "wasm32"))]
+            backup_dir,
+            #[cfg(not(target_arch = "wasm32"))]
+            backup_count,
+            excluded_from_totals,
+            summary_period_days,
         } = self;
+
+        if let Some(lock) = pin_lock {
+            if !state.pin_gate.unlocked {
+                let mut pin_tried = false;
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.heading("Enter your PIN");
+                    let response = ui
+                        .add(egui::TextEdit::singleline(&mut state.pin_gate.entry).password(true));
+                    response.request_focus();
+                    if (response.lost_focus() && ui.input().key_pressed(egui::Key::Enter))
+                        || ui.button("Unlock").clicked()
+                    {
+                        state.pin_gate.try_unlock(lock, chrono::offset::Utc::now());
+                        pin_tried = true;
+                    }
+                    if let Some(error) = &state.pin_gate.error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+                });
+                if pin_tried && !self.state.pin_gate.unlocked {
+                    // Save straight away, so closing before the next autosave can't reset
+                    // the wrong PIN count
+                    if let Some(storage) = frame.storage_mut() {
+                        self.save(storage);
+                        storage.flush();
+                    }
+                }
+                return;
+            }
+        }
         let mut entries_to_delete = Vec::new();
         let mut projects_to_delete = Vec::new();
+        let utc_offset = utc_offset_from_minutes(*utc_offset_minutes);
+        let recent_notes = recent_notes(time_sheet_entries, RECENT_NOTES_LIMIT);
+        // Projects new work can be started or added for
+        let active_project_types: Vec<String> = project_types
+            .iter()
+            .filter(|project_type| !archived_projects.contains(*project_type))
+            .cloned()
+            .collect();
 
-        ctx.request_repaint_after(std::time::Duration::from_secs_f32(1.0));
+        // Often enough while a timer runs that its seconds tick over evenly
+        if state.timers.is_empty() {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f32(1.0));
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f32(0.25));
+        }
 
-        #[cfg(not(target_arch = "wasm32"))] // no File->Quit on web pages!
-        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
-            // The top panel is often a good place for a menu bar:
+        let generate_summary_pressed = {
+            let input = ctx.input();
+            input.modifiers.command && input.key_pressed(GENERATE_SUMMARY_KEY)
+        };
+        if generate_summary_pressed {
+            state.generate_time_sheet_summary(
+                time_sheet_entries,
+                *summary_period_days,
+                &utc_offset,
+                excluded_from_totals,
+                project_types,
+            );
+        }
 
-            egui::menu::bar(ui, |ui| {
-                ui.menu_button("File", |ui| {
-                    if ui.button("Quit").clicked() {
-                        _frame.close();
-                    }
+        let now = chrono::offset::Utc::now();
+        let had_input = {
+            let input = ctx.input();
+            !input.events.is_empty() || input.pointer.is_moving()
+        };
+        if had_input {
+            state.last_active = now;
+            for timer in state.timers.values_mut() {
+                if timer.paused_at.is_some() && !timer.paused_manually {
+                    timer.resume(now);
+                }
+            }
+        } else if *auto_pause_enabled
+            && now - state.last_active > Duration::minutes(*auto_pause_minutes)
+        {
+            for timer in state.timers.values_mut() {
+                // Pause from the last input so the idle time isn't counted either
+                timer.pause(state.last_active.max(timer.started_at), false);
+            }
+        } else if *idle_prompt_enabled
+            && !*auto_pause_enabled
+            && !state.timers.is_empty()
+            && state.idle_since.is_none()
+            && now - state.last_active > Duration::minutes(*idle_prompt_minutes)
+        {
+            state.idle_since = Some(state.last_active);
+        }
+
+        if let Some(cutoff) = *daily_cutoff {
+            let mut cut_off_timers: Vec<(String, DateTime<Utc>)> = state
+                .timers
+                .iter()
+                .map(|(project_type, timer)| {
+                    (
+                        project_type.to_string(),
+                        timer.next_cutoff(cutoff, &utc_offset),
+                    )
+                })
+                .filter(|(_, cutoff_at)| *cutoff_at <= now)
+                .collect();
+            cut_off_timers.sort();
+            for (project_type, cutoff_at) in cut_off_timers {
+                if let Some(entry) = state.finish_timer(
+                    &project_type,
+                    cutoff_at,
+                    *snap_timer_to_quarter_hour,
+                    entry_rounding,
+                ) {
+                    time_sheet_entries.push(entry);
+                }
+                state.cutoff_notices.push(format!(
+                    "The {} timer ran past {} on {}, so it was finished then and the time after wasn't logged",
+                    project_type,
+                    cutoff.format("%R"),
+                    cutoff_at.with_timezone(&utc_offset).format("%F"),
+                ));
+            }
+        }
+
+        let (toggle_timer_pressed, focus_manual_add_pressed) = {
+            let input = ctx.input();
+            (
+                input.modifiers.command && input.key_pressed(TOGGLE_TIMER_KEY),
+                input.modifiers.command && input.key_pressed(FOCUS_MANUAL_ADD_KEY),
+            )
+        };
+        if toggle_timer_pressed {
+            if let Some(project_type) = state.selected_project_type.clone() {
+                if let Some(timer) = state.timers.get(&project_type) {
+                    let finished_at = timer.stale_since.unwrap_or(now);
+                    if timer.runs_longer_than(now, *long_timer_hours) {
+                        state.long_finish = Some((project_type, None));
+                    } else if let Some(entry) = state.finish_timer(
+                        &project_type,
+                        finished_at,
+                        *snap_timer_to_quarter_hour,
+                        entry_rounding,
+                    ) {
+                        time_sheet_entries.push(entry);
+                    }
+                } else {
+                    state.start_timer(&project_type, now);
+                }
+            }
+        }
+        // The manual add form is only shown while no timers are running
+        if focus_manual_add_pressed && state.timers.is_empty() {
+            state.focus_manual_add = true;
+        }
+
+        if !ctx.wants_keyboard_input() {
+            let input = ctx.input();
+            for (key, project_type) in PROJECT_SELECT_KEYS.iter().zip(active_project_types.iter()) {
+                if input.key_pressed(*key) {
+                    state.selected_project_type = Some(project_type.to_string());
+                }
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))] // no File->Quit on web pages!
+        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
+            // The top panel is often a good place for a menu bar:
+
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    if ui.button("Quit").clicked() {
+                        frame.close();
+                    }
                 });
             });
         });
 
-        egui::SidePanel::left("side_panel").show(ctx, |ui| {
-            ui.heading("Select a project");
-            if state.work_start_time.is_none() {
+        let side_panel = egui::SidePanel::left("side_panel")
+            .resizable(true)
+            .default_width(*side_panel_width)
+            .show(ctx, |ui| {
+                if *compact_mode {
+                    ui.spacing_mut().item_spacing = egui::vec2(4.0, 2.0);
+                    ui.spacing_mut().button_padding = egui::vec2(2.0, 0.0);
+                }
+                ui.checkbox(compact_mode, "compact mode")
+                    .on_hover_text("hides the configuration and entries to keep the window small");
+                if !state.cutoff_notices.is_empty() {
+                    for notice in state.cutoff_notices.iter() {
+                        ui.colored_label(egui::Color32::YELLOW, notice);
+                    }
+                    if ui.button("Dismiss").clicked() {
+                        state.cutoff_notices.clear();
+                    }
+                    ui.separator();
+                }
+                ui.heading("Select a project");
                 egui::ComboBox::from_label("Select Project")
                     .selected_text(match &state.selected_project_type {
                         Some(project_type) => project_type.to_string(),
                         None => "select a project".to_owned(),
                     })
                     .show_ui(ui, |ui| {
-                        for project_type in project_types.as_slice() {
+                        for (index, project_type) in active_project_types.iter().enumerate() {
+                            let label = if index < PROJECT_SELECT_KEYS.len() {
+                                format!("{}: {}", index + 1, project_type)
+                            } else {
+                                project_type.to_string()
+                            };
                             ui.selectable_value(
                                 &mut state.selected_project_type,
                                 Some(project_type.to_string()),
-                                project_type,
+                                label,
                             );
                         }
                     });
 
-                if state.selected_project_type.is_some()
-                    && ui.button("start work on project").clicked()
-                {
-                    state.work_start_time = Some(chrono::offset::Utc::now());
-                }
-            } else {
-                let duration = match state.work_start_time {
-                    Some(dt) => chrono::offset::Utc::now() - dt,
-                    None => Duration::seconds(0),
-                };
-
-                ui.label(format!("Time elapsed: {}", format_duration(&duration)));
-                ui.text_edit_multiline(&mut state.current_notes);
-                if ui.button("Finish project work").clicked() {
-                    time_sheet_entries.push(TimeSheetEntry {
-                        project_type: state.selected_project_type.as_ref().unwrap().to_string(),
-                        work_start_datetime: state.work_start_time.unwrap(),
-                        work_end_datetime: chrono::offset::Utc::now(),
-                        notes: state.current_notes.to_string(),
-                    });
-                    state.work_start_time = None;
-                    state.current_notes = String::new();
+                if let Some(project_type) = state.selected_project_type.clone() {
+                    if ui
+                        .add_enabled(
+                            !state.timers.contains_key(&project_type),
+                            egui::Button::new("start work on project"),
+                        )
+                        .on_hover_text("Ctrl+T")
+                        .on_disabled_hover_text("a timer is already running for this project")
+                        .clicked()
+                    {
+                        state.start_timer(&project_type, chrono::offset::Utc::now());
+                    }
                 }
-            }
 
-            if state.work_start_time.is_none() {
-                ui.add_space(20.0);
-                ui.separator();
-                egui::containers::CollapsingHeader::new("Project Configuration").show(ui, |ui| {
-                    egui::Grid::new("project_types_grid").show(ui, |grid_ui| {
-                        grid_ui.label("project type");
-                        grid_ui.end_row();
+                let mut running_projects: Vec<String> = state.timers.keys().cloned().collect();
+                running_projects.sort();
+                // Timers to finish, the project to start next in their place, and when they
+                // finish if that's been picked rather than now
+                let mut timers_to_finish: Vec<(String, Option<String>, Option<DateTime<Utc>>)> =
+                    Vec::new();
+                for project_type in running_projects.iter() {
+                    let timer = state.timers.get_mut(project_type).unwrap();
+                    ui.separator();
+                    ui.strong(project_type);
+                    if let Some(stale_since) = timer.stale_since {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!(
+                                "This timer was still running when the app was last open at {}",
+                                stale_since.with_timezone(&utc_offset).format("%F %R")
+                            ),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button("Finish it then").clicked() {
+                                timers_to_finish.push((project_type.to_string(), None, None));
+                            }
+                            if ui.button("Keep running").clicked() {
+                                timer.stale_since = None;
+                            }
+                        });
+                    }
+                    let duration = timer.elapsed(now);
 
-                        for (index, prj) in project_types.iter().enumerate() {
-                            grid_ui.label(prj.to_string());
-                            if grid_ui.button("delete project type").clicked() {
-                                projects_to_delete.push(index);
+                    ui.label(format!("Time elapsed: {}", format_stopwatch(&duration)));
+                    ui.horizontal(|ui| {
+                        if timer.paused_at.is_none() {
+                            if ui.button("Pause").clicked() {
+                                timer.pause(now, true);
+                            }
+                        } else if timer.paused_manually {
+                            ui.label("Paused");
+                            if ui.button("Resume").clicked() {
+                                timer.resume(now);
                             }
-                            grid_ui.end_row();
+                        } else {
+                            ui.label("Paused while idle, resumes on activity");
                         }
                     });
+                    if *snap_timer_to_quarter_hour || entry_rounding.increment_minutes > 0 {
+                        ui.label(format!(
+                            "Will be stored as: {}",
+                            format_duration(&stored_duration(
+                                duration,
+                                *snap_timer_to_quarter_hour,
+                                entry_rounding
+                            ))
+                        ));
+                    }
+                    ui.horizontal(|ui| {
+                        // The cursor and selection are kept under the widget's id. An id from
+                        // the layout would change whenever a line above it comes or goes, like
+                        // the paused label, so the notes are given their own.
+                        ui.add(
+                            egui::TextEdit::multiline(&mut timer.notes)
+                                .id_source(("timer_notes", project_type)),
+                        );
+                        show_recent_notes_menu(
+                            ui,
+                            ("timer_recent_notes", project_type),
+                            &recent_notes,
+                            &mut timer.notes,
+                        );
+                    });
                     ui.horizontal(|ui| {
-                        ui.label("Project type to add: ");
-                        ui.text_edit_singleline(&mut state.new_project_type);
-                        if ui.button("Add Project Type").clicked() {
-                            project_types.push(state.new_project_type.to_owned());
-                            state.new_project_type = "".to_string();
+                        if ui
+                            .button("Finish project work")
+                            .on_hover_text("Ctrl+T while this project is selected")
+                            .clicked()
+                        {
+                            timers_to_finish.push((project_type.to_string(), None, None));
+                        }
+                        if timer.finish_at.is_none()
+                            && ui
+                                .button("Finish earlier")
+                                .on_hover_text("for when the work stopped a while ago")
+                                .clicked()
+                        {
+                            timer.finish_at =
+                                Some(now.with_timezone(&utc_offset).format("%H:%M").to_string());
                         }
                     });
-                });
+                    let mut cancel_finish_at = false;
+                    if let Some(finish_at) = &mut timer.finish_at {
+                        let finished_at = ui
+                            .horizontal(|ui| {
+                                ui.label("finished at");
+                                ui.add(egui::TextEdit::singleline(finish_at).desired_width(50.0));
+                                let finished_at =
+                                    retroactive_end(timer.started_at, finish_at, now, &utc_offset);
+                                if ui
+                                    .add_enabled(finished_at.is_ok(), egui::Button::new("Finish"))
+                                    .clicked()
+                                {
+                                    timers_to_finish.push((
+                                        project_type.to_string(),
+                                        None,
+                                        finished_at.clone().ok(),
+                                    ));
+                                }
+                                if ui.button("Cancel").clicked() {
+                                    cancel_finish_at = true;
+                                }
+                                finished_at
+                            })
+                            .inner;
+                        if let Err(error) = finished_at {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
+                    }
+                    if cancel_finish_at {
+                        timer.finish_at = None;
+                    }
 
-                egui::containers::CollapsingHeader::new("Manual Add").show(ui, |ui| {
                     ui.horizontal(|ui| {
-                        ui.label("project");
-                        ui.text_edit_singleline(&mut state.manual_add_project);
+                        egui::ComboBox::from_id_source(("next_project_type", project_type))
+                            .selected_text(match &timer.next_project_type {
+                                Some(project_type) => project_type.to_string(),
+                                None => "next project".to_owned(),
+                            })
+                            .show_ui(ui, |ui| {
+                                for project_type in active_project_types.iter() {
+                                    ui.selectable_value(
+                                        &mut timer.next_project_type,
+                                        Some(project_type.to_string()),
+                                        project_type,
+                                    );
+                                }
+                            });
+                        if timer.next_project_type.is_some()
+                            && ui.button("Finish and start next").clicked()
+                        {
+                            timers_to_finish.push((
+                                project_type.to_string(),
+                                timer.next_project_type.take(),
+                                None,
+                            ));
+                        }
                     });
+                }
+                for (project_type, next_project_type, finished_at) in timers_to_finish {
+                    let timer = state.timers.get(&project_type);
+                    // A picked end time has already been looked at, so it isn't asked about
+                    if finished_at.is_none()
+                        && timer.map_or(false, |timer| {
+                            timer.runs_longer_than(now, *long_timer_hours)
+                        })
+                    {
+                        state.long_finish = Some((project_type, next_project_type));
+                        continue;
+                    }
+                    let finished_at = finished_at
+                        .or_else(|| timer.and_then(|timer| timer.stale_since))
+                        .unwrap_or(now);
+                    // The next timer picks up where the finished entry leaves off
+                    let mut next_started_at = now;
+                    if let Some(entry) = state.finish_timer(
+                        &project_type,
+                        finished_at,
+                        *snap_timer_to_quarter_hour,
+                        entry_rounding,
+                    ) {
+                        next_started_at = entry.work_end_datetime;
+                        time_sheet_entries.push(entry);
+                    }
+                    if let Some(next_project_type) = next_project_type {
+                        state.start_timer(&next_project_type, next_started_at);
+                        state.selected_project_type = Some(next_project_type);
+                    }
+                }
 
-                    ui.horizontal(|ui| {
-                        ui.label("date");
-                        ui.add(
-                            egui_extras::DatePickerButton::new(&mut state.manual_add_date)
-                                .id_source("manual_project_date"),
+                show_today_breakdown(
+                    ui,
+                    time_sheet_entries,
+                    state,
+                    now,
+                    &utc_offset,
+                    *daily_goal_hours,
+                    excluded_from_totals,
+                );
+
+                if state.timers.is_empty() && !*compact_mode {
+                    ui.add_space(20.0);
+                    ui.separator();
+                    egui::containers::CollapsingHeader::new("Project Configuration").show(
+                        ui,
+                        |ui| {
+                            egui::Grid::new("project_types_grid").show(ui, |grid_ui| {
+                                grid_ui.label("project type");
+                                grid_ui.label("unit label");
+                                grid_ui.label("hourly rate");
+                                grid_ui.label("client");
+                                grid_ui.label("color");
+                                grid_ui.end_row();
+
+                                for (index, prj) in project_types.iter().enumerate() {
+                                    grid_ui.label(prj.to_string());
+                                    let mut unit_label =
+                                        project_unit_labels.get(prj).cloned().unwrap_or_default();
+                                    if grid_ui
+                                        .add(
+                                            egui::TextEdit::singleline(&mut unit_label)
+                                                .hint_text("none")
+                                                .desired_width(80.0),
+                                        )
+                                        .changed()
+                                    {
+                                        if unit_label.is_empty() {
+                                            project_unit_labels.remove(prj);
+                                        } else {
+                                            project_unit_labels.insert(prj.to_string(), unit_label);
+                                        }
+                                    }
+                                    grid_ui.horizontal(|ui| match hourly_rates.get_mut(prj) {
+                                        Some(hourly_rate) => {
+                                            ui.add(
+                                                egui::DragValue::new(hourly_rate)
+                                                    .clamp_range(0.0..=f64::MAX)
+                                                    .speed(1.0)
+                                                    .fixed_decimals(2),
+                                            );
+                                            if ui.small_button("x").clicked() {
+                                                hourly_rates.remove(prj);
+                                            }
+                                        }
+                                        None => {
+                                            if ui.small_button("add rate").clicked() {
+                                                hourly_rates.insert(prj.to_string(), 0.0);
+                                            }
+                                        }
+                                    });
+                                    let mut client =
+                                        project_clients.get(prj).cloned().unwrap_or_default();
+                                    if grid_ui
+                                        .add(
+                                            egui::TextEdit::singleline(&mut client)
+                                                .hint_text("none")
+                                                .desired_width(80.0),
+                                        )
+                                        .changed()
+                                    {
+                                        if client.trim().is_empty() {
+                                            project_clients.remove(prj);
+                                        } else {
+                                            project_clients.insert(prj.to_string(), client);
+                                        }
+                                    }
+                                    let mut color = project_color(project_colors, prj);
+                                    if egui::color_picker::color_edit_button_srgba(
+                                        grid_ui,
+                                        &mut color,
+                                        egui::color_picker::Alpha::Opaque,
+                                    )
+                                    .changed()
+                                    {
+                                        project_colors.insert(prj.to_string(), color.to_array());
+                                    }
+                                    let mut counts_in_totals = !excluded_from_totals.contains(prj);
+                                    if grid_ui
+                                        .checkbox(&mut counts_in_totals, "count in totals")
+                                        .on_hover_text("unticked projects still show, like Lunch")
+                                        .changed()
+                                    {
+                                        if counts_in_totals {
+                                            excluded_from_totals.remove(prj);
+                                        } else {
+                                            excluded_from_totals.insert(prj.to_string());
+                                        }
+                                    }
+                                    let mut archived = archived_projects.contains(prj);
+                                    if grid_ui
+                                        .checkbox(&mut archived, "archived")
+                                        .on_hover_text("hides the project when starting new work")
+                                        .changed()
+                                    {
+                                        if archived {
+                                            archived_projects.insert(prj.to_string());
+                                            if state.selected_project_type.as_ref() == Some(prj) {
+                                                state.selected_project_type = None;
+                                            }
+                                        } else {
+                                            archived_projects.remove(prj);
+                                        }
+                                    }
+                                    if grid_ui.button("rename").clicked() {
+                                        state.project_rename =
+                                            Some((prj.to_string(), prj.to_string()));
+                                    }
+                                    if grid_ui.button("delete project type").clicked() {
+                                        state.pending_delete =
+                                            Some(PendingDelete::ProjectType(index));
+                                    }
+                                    grid_ui.end_row();
+                                }
+                            });
+                            if let Some((old_name, new_name)) = &mut state.project_rename {
+                                let mut finished = false;
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("Rename {} to", old_name));
+                                    ui.text_edit_singleline(new_name);
+                                });
+                                // Renaming would change entries that have been signed off
+                                let locked_entries = time_sheet_entries
+                                    .iter()
+                                    .filter(|entry| {
+                                        entry.project_type == *old_name
+                                            && is_entry_locked(period_submissions, entry, &utc_offset)
+                                    })
+                                    .count();
+                                if locked_entries > 0 {
+                                    ui.colored_label(
+                                        egui::Color32::RED,
+                                        format!(
+                                            "{} can't be renamed, {} of its entries are in locked periods",
+                                            old_name, locked_entries
+                                        ),
+                                    );
+                                }
+                                ui.horizontal(|ui| {
+                                    let merge_into =
+                                        merge_target(project_types, old_name, new_name.trim())
+                                            .cloned();
+                                    let merging = merge_into.is_some();
+                                    let new_name =
+                                        merge_into.unwrap_or_else(|| new_name.trim().to_string());
+                                    let label = if merging {
+                                        format!("merge into {}", new_name)
+                                    } else {
+                                        "rename".to_string()
+                                    };
+                                    if ui
+                                        .add_enabled(
+                                            !new_name.is_empty()
+                                                && new_name != *old_name
+                                                && locked_entries == 0,
+                                            egui::Button::new(label),
+                                        )
+                                        .clicked()
+                                    {
+                                        rename_project(
+                                            project_types,
+                                            time_sheet_entries,
+                                            old_name,
+                                            &new_name,
+                                        );
+                                        // Settings move along unless the merged project has its own
+                                        if let Some(unit_label) =
+                                            project_unit_labels.remove(old_name)
+                                        {
+                                            project_unit_labels
+                                                .entry(new_name.to_string())
+                                                .or_insert(unit_label);
+                                        }
+                                        if let Some(hourly_rate) = hourly_rates.remove(old_name) {
+                                            hourly_rates
+                                                .entry(new_name.to_string())
+                                                .or_insert(hourly_rate);
+                                        }
+                                        if let Some(client) = project_clients.remove(old_name) {
+                                            project_clients
+                                                .entry(new_name.to_string())
+                                                .or_insert(client);
+                                        }
+                                        // Merging into an active project keeps it active
+                                        if archived_projects.remove(old_name) && !merging {
+                                            archived_projects.insert(new_name.to_string());
+                                        }
+                                        if excluded_from_totals.remove(old_name) && !merging {
+                                            excluded_from_totals.insert(new_name.to_string());
+                                        }
+                                        if let Some(color) = project_colors.remove(old_name) {
+                                            project_colors
+                                                .entry(new_name.to_string())
+                                                .or_insert(color);
+                                        }
+                                        if state.selected_project_type.as_ref() == Some(old_name) {
+                                            state.selected_project_type =
+                                                Some(new_name.to_string());
+                                        }
+                                        if state.manual_add_project == *old_name {
+                                            state.manual_add_project = new_name.to_string();
+                                        }
+                                        finished = true;
+                                    }
+                                    if ui.button("cancel").clicked() {
+                                        finished = true;
+                                    }
+                                });
+                                if finished {
+                                    state.project_rename = None;
+                                }
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label("Project type to add: ");
+                                let response = ui.text_edit_singleline(&mut state.new_project_type);
+                                let entered = response.lost_focus()
+                                    && ui.input().key_pressed(egui::Key::Enter);
+                                if ui.button("Add Project Type").clicked() || entered {
+                                    match add_project_type(project_types, &state.new_project_type) {
+                                        Ok(_) => {
+                                            state.new_project_type = "".to_string();
+                                            state.new_project_type_error = None;
+                                        }
+                                        Err(error) => state.new_project_type_error = Some(error),
+                                    }
+                                }
+                            });
+                            if let Some(error) = &state.new_project_type_error {
+                                ui.colored_label(egui::Color32::RED, error);
+                            }
+                        },
+                    );
+
+                    egui::containers::CollapsingHeader::new("Manual Add")
+                        .open(if state.focus_manual_add {
+                            Some(true)
+                        } else {
+                            None
+                        })
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .button("Copy yesterday's entries to today")
+                                    .on_hover_text("same projects, times and notes")
+                                    .clicked()
+                                {
+                                    let today = chrono::offset::Utc::now()
+                                        .with_timezone(&utc_offset)
+                                        .date_naive();
+                                    let copies = copy_day(
+                                        time_sheet_entries,
+                                        today.pred(),
+                                        today,
+                                        &utc_offset,
+                                    );
+                                    state.copy_day_status = Some(if copies.is_empty() {
+                                        "Yesterday has no entries to copy".to_string()
+                                    } else if is_date_locked(period_submissions, today) {
+                                        "Today is in a locked period".to_string()
+                                    } else {
+                                        let copied = copies.len();
+                                        time_sheet_entries.extend(copies);
+                                        format!("Copied {} entries to today", copied)
+                                    });
+                                }
+                                if let Some(copy_day_status) = &state.copy_day_status {
+                                    ui.label(copy_day_status);
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("quick add");
+                                let quick_entry_response = ui.add(
+                                    egui::TextEdit::singleline(&mut state.quick_entry)
+                                        .hint_text("Dev 1h30m fixed the build"),
+                                );
+                                if quick_entry_response.lost_focus()
+                                    && ui.input().key_pressed(egui::Key::Enter)
+                                {
+                                    let today = local_today(&utc_offset).naive_utc();
+                                    match parse_quick_entry(
+                                        &state.quick_entry,
+                                        &active_project_types,
+                                        &today,
+                                        &utc_offset,
+                                    ) {
+                                        Ok(_) if is_date_locked(period_submissions, today) => {
+                                            state.quick_entry_error =
+                                                Some("Today is in a locked period".to_string());
+                                        }
+                                        Ok(mut entry) => {
+                                            entry.round_length(entry_rounding);
+                                            time_sheet_entries.push(entry);
+                                            state.quick_entry.clear();
+                                            state.quick_entry_error = None;
+                                        }
+                                        Err(error) => state.quick_entry_error = Some(error),
+                                    }
+                                }
+                            });
+                            if let Some(error) = &state.quick_entry_error {
+                                ui.colored_label(egui::Color32::RED, error);
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.label("project");
+                                egui::ComboBox::from_id_source("manual_add_project")
+                                    .selected_text(if state.manual_add_project.is_empty() {
+                                        "select a project"
+                                    } else {
+                                        &state.manual_add_project
+                                    })
+                                    .show_ui(ui, |ui| {
+                                        for project_type in active_project_types.iter() {
+                                            ui.selectable_value(
+                                                &mut state.manual_add_project,
+                                                project_type.to_string(),
+                                                project_type,
+                                            );
+                                        }
+                                    });
+                            });
+
+                            // Enter in the single line fields, or Ctrl+Enter in the notes, adds
+                            // the entry like the Add button
+                            let mut submitted = false;
+                            ui.horizontal(|ui| {
+                                ui.label("date");
+                                ui.add(
+                                    egui_extras::DatePickerButton::new(&mut state.manual_add_date)
+                                        .id_source("manual_project_date"),
+                                );
+                                if ui
+                                    .small_button("filter end")
+                                    .on_hover_text("Use the end date of the entry filters")
+                                    .clicked()
+                                {
+                                    state.manual_add_date =
+                                        Utc.from_utc_date(&state.time_sheet_filters.end_date);
+                                }
+                                ui.checkbox(keep_manual_add_date, "keep date after add");
+                                ui.label("minutes");
+                                let minutes_response = ui.add(
+                                    egui::TextEdit::singleline(&mut state.manual_add_minutes)
+                                        .desired_width(60.0),
+                                );
+                                if state.focus_manual_add {
+                                    minutes_response.request_focus();
+                                    state.focus_manual_add = false;
+                                }
+                                submitted |= minutes_response.lost_focus()
+                                    && ui.input().key_pressed(egui::Key::Enter);
+                                for step in MANUAL_MINUTES_STEPS {
+                                    if ui.small_button(format!("{:+}", step)).clicked() {
+                                        state.manual_add_minutes =
+                                            step_minutes(&state.manual_add_minutes, step);
+                                    }
+                                }
+                            });
+
+                            ui.horizontal(|ui| {
+                                ui.label("units");
+                                let units_response = ui.add(
+                                    egui::TextEdit::singleline(&mut state.manual_add_units)
+                                        .hint_text("optional"),
+                                );
+                                submitted |= units_response.lost_focus()
+                                    && ui.input().key_pressed(egui::Key::Enter);
+                                if let Some(unit_label) =
+                                    project_unit_labels.get(&state.manual_add_project)
+                                {
+                                    ui.label(unit_label);
+                                }
+                            });
+
+                            ui.horizontal(|ui| {
+                                ui.label("tags");
+                                let tags_response = ui.add(
+                                    egui::TextEdit::singleline(&mut state.manual_add_tags)
+                                        .hint_text("comma separated"),
+                                );
+                                submitted |= tags_response.lost_focus()
+                                    && ui.input().key_pressed(egui::Key::Enter);
+                                ui.checkbox(&mut state.manual_add_billable, "billable");
+                            });
+
+                            ui.horizontal(|ui| {
+                                let notes_response =
+                                    ui.text_edit_multiline(&mut state.manual_add_notes);
+                                if notes_response.has_focus()
+                                    && ui.input().modifiers.command
+                                    && ui.input().key_pressed(egui::Key::Enter)
+                                {
+                                    // The notes field has already taken the Enter as a new line
+                                    if state.manual_add_notes.ends_with('\n') {
+                                        state.manual_add_notes.pop();
+                                    }
+                                    submitted = true;
+                                }
+                                show_recent_notes_menu(
+                                    ui,
+                                    "manual_add_recent_notes",
+                                    &recent_notes,
+                                    &mut state.manual_add_notes,
+                                );
+                            });
+                            let minutes = parse_manual_minutes(&state.manual_add_minutes);
+                            if let Err(error) = &minutes {
+                                // An empty field hasn't been filled in yet rather than being wrong
+                                if !state.manual_add_minutes.trim().is_empty() {
+                                    ui.colored_label(egui::Color32::RED, error);
+                                }
+                            }
+                            let date_locked = is_date_locked(
+                                period_submissions,
+                                state.manual_add_date.naive_utc(),
+                            );
+                            if date_locked {
+                                ui.colored_label(egui::Color32::RED, "That date is in a locked period");
+                            }
+                            // The selected project may have been deleted since it was picked
+                            let project_selected =
+                                active_project_types.contains(&state.manual_add_project);
+                            let can_add = project_selected && minutes.is_ok() && !date_locked;
+                            let add_clicked = ui
+                                .add_enabled(can_add, egui::Button::new("Add"))
+                                .on_disabled_hover_text(
+                                    "select a project and enter the minutes worked",
+                                )
+                                .clicked();
+                            if add_clicked || (submitted && can_add) {
+                                let minutes = minutes.unwrap();
+                                let mut entry = TimeSheetEntry::from_minutes(
+                                    &state.manual_add_project,
+                                    minutes,
+                                    &state.manual_add_notes,
+                                    &state.manual_add_date.naive_utc(),
+                                    &utc_offset,
+                                );
+                                entry.round_length(entry_rounding);
+                                entry.units = state
+                                    .manual_add_units
+                                    .trim()
+                                    .parse::<f64>()
+                                    .ok()
+                                    .filter(|units| units.is_finite());
+                                entry.tags = parse_tags(&state.manual_add_tags);
+                                entry.billable = state.manual_add_billable;
+                                time_sheet_entries.push(entry);
+                                if !*keep_manual_add_date {
+                                    state.manual_add_date = local_today(&utc_offset);
+                                }
+                            }
+                        });
+
+                    egui::containers::CollapsingHeader::new("Settings").show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("summary rounding").on_hover_text(
+                                "Rounds each project's total in the summary and exports, \
+                                the stored entries are left as they are",
+                            );
+                            rounding_settings_ui(ui, "summary_rounding", rounding);
+                        });
+                        ui.checkbox(
+                            snap_timer_to_quarter_hour,
+                            "snap finished timers to the nearest quarter hour",
                         );
-                        ui.label("minutes");
-                        ui.text_edit_singleline(&mut state.manual_add_minutes);
+                        ui.horizontal(|ui| {
+                            ui.label("new entry rounding").on_hover_text(
+                                "Rounds the length of each new entry when it is stored, \
+                                after any quarter hour snap. An entry with time in it is \
+                                never rounded down to nothing",
+                            );
+                            rounding_settings_ui(ui, "entry_rounding", entry_rounding);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("expected hours per week");
+                            ui.add(egui::DragValue::new(weekly_hours).clamp_range(0.0..=168.0));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("daily goal");
+                            ui.add(
+                                egui::DragValue::new(daily_goal_hours)
+                                    .clamp_range(0.0..=24.0)
+                                    .speed(0.25)
+                                    .suffix(" hours"),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.checkbox(auto_pause_enabled, "pause timer after");
+                            ui.add(
+                                egui::DragValue::new(auto_pause_minutes)
+                                    .clamp_range(1..=240)
+                                    .suffix(" idle minutes"),
+                            );
+                        });
+                        ui.add_enabled_ui(!*auto_pause_enabled, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.checkbox(idle_prompt_enabled, "ask about idle time after");
+                                ui.add(
+                                    egui::DragValue::new(idle_prompt_minutes)
+                                        .clamp_range(1..=240)
+                                        .suffix(" idle minutes"),
+                                );
+                            })
+                            .response
+                            .on_disabled_hover_text(
+                                "pausing the timer already leaves out idle time",
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("confirm finishing timers over");
+                            ui.add(
+                                egui::DragValue::new(long_timer_hours)
+                                    .clamp_range(1..=48)
+                                    .suffix(" hours"),
+                            );
+                        });
+                        ui.horizontal(|ui| {
+                            let mut cutoff_enabled = daily_cutoff.is_some();
+                            if ui
+                                .checkbox(&mut cutoff_enabled, "finish timers at")
+                                .changed()
+                            {
+                                *daily_cutoff = if cutoff_enabled {
+                                    NaiveTime::from_hms_opt(18, 0, 0)
+                                } else {
+                                    None
+                                };
+                            }
+                            if let Some(cutoff) = daily_cutoff {
+                                let (mut hour, mut minute) = (cutoff.hour(), cutoff.minute());
+                                ui.add(egui::DragValue::new(&mut hour).clamp_range(0..=23));
+                                ui.label(":");
+                                ui.add(egui::DragValue::new(&mut minute).clamp_range(0..=59));
+                                if let Some(updated) = NaiveTime::from_hms_opt(hour, minute, 0) {
+                                    *cutoff = updated;
+                                }
+                            }
+                        });
+                        egui::Grid::new("report_labels_grid").show(ui, |ui| {
+                            ui.label("your name");
+                            ui.text_edit_singleline(&mut report_labels.name);
+                            ui.end_row();
+                            ui.label("organization");
+                            ui.text_edit_singleline(organization);
+                            ui.end_row();
+                            ui.label("report title");
+                            ui.text_edit_singleline(&mut report_labels.title);
+                            ui.end_row();
+                            ui.label("report footer");
+                            ui.text_edit_singleline(&mut report_labels.footer);
+                            ui.end_row();
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("grid spacing");
+                            ui.radio_value(grid_density, GridDensity::Compact, "compact");
+                            ui.radio_value(grid_density, GridDensity::Comfortable, "comfortable");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("entry lengths");
+                            ui.radio_value(duration_display, DurationDisplay::Clock, "clock");
+                            ui.radio_value(duration_display, DurationDisplay::Decimal, "decimal");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("working days");
+                            let mut weekday = Weekday::Mon;
+                            for _ in 0..7 {
+                                let mut is_working_day = working_days.contains(&weekday);
+                                if ui
+                                    .checkbox(&mut is_working_day, format!("{:?}", weekday))
+                                    .changed()
+                                {
+                                    if is_working_day {
+                                        working_days.push(weekday);
+                                    } else {
+                                        working_days.retain(|day| *day != weekday);
+                                    }
+                                }
+                                weekday = weekday.succ();
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("weeks start on");
+                            egui::ComboBox::from_id_source("week_start")
+                                .selected_text(format!("{:?}", week_start))
+                                .show_ui(ui, |ui| {
+                                    let mut weekday = Weekday::Mon;
+                                    for _ in 0..7 {
+                                        ui.selectable_value(
+                                            week_start,
+                                            weekday,
+                                            format!("{:?}", weekday),
+                                        );
+                                        weekday = weekday.succ();
+                                    }
+                                });
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("time zone");
+                            let mut offset_hours = *utc_offset_minutes as f64 / 60.0;
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut offset_hours)
+                                        .clamp_range(-12.0..=14.0)
+                                        .speed(0.25)
+                                        .fixed_decimals(2)
+                                        .prefix("UTC "),
+                                )
+                                .changed()
+                            {
+                                // Offsets are whole quarter hours
+                                *utc_offset_minutes = (offset_hours * 4.0).round() as i32 * 15;
+                            }
+                            if ui.button("use system offset").clicked() {
+                                *utc_offset_minutes = local_utc_offset_minutes();
+                            }
+                        });
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            #[cfg(not(target_arch = "wasm32"))]
+                            ui.text_edit_singleline(&mut state.config_path);
+                            if ui
+                                .button("Export config")
+                                .on_hover_text("Saves the project types and settings, no entries")
+                                .clicked()
+                            {
+                                state.config_action = Some(ConfigAction::Export);
+                            }
+                            if ui
+                                .button("Import config")
+                                .on_hover_text(
+                                    "Adds the project types and takes the rates and settings",
+                                )
+                                .clicked()
+                            {
+                                state.config_action = Some(ConfigAction::Import);
+                            }
+                        });
+                        if let Some(config_status) = &state.config_status {
+                            ui.label(config_status);
+                        }
+                        #[cfg(not(target_arch = "wasm32"))]
+                        {
+                            ui.horizontal(|ui| {
+                                ui.label("backups");
+                                ui.text_edit_singleline(backup_dir).on_hover_text(
+                                    "a relative folder is kept with the app's saved data, \
+                                     leave empty to stop backing up",
+                                );
+                                ui.label("keep");
+                                ui.add(egui::DragValue::new(backup_count).clamp_range(1..=100));
+                            });
+                            ui.horizontal(|ui| {
+                                ui.menu_button("Restore from backup", |ui| {
+                                    let backups = list_backups(&resolve_backup_dir(backup_dir));
+                                    if backups.is_empty() {
+                                        ui.label("no backups yet");
+                                    }
+                                    for backup in backups {
+                                        let name = backup
+                                            .file_name()
+                                            .map(|name| name.to_string_lossy().to_string())
+                                            .unwrap_or_default();
+                                        if ui
+                                            .button(name)
+                                            .on_hover_text(
+                                                "replaces the entries outside locked periods, the current ones are backed up first",
+                                            )
+                                            .clicked()
+                                        {
+                                            let restored = write_backup(
+                                                &resolve_backup_dir(backup_dir),
+                                                time_sheet_entries,
+                                                chrono::offset::Utc::now(),
+                                                // Keep the chosen backup even if it's the oldest
+                                                *backup_count + 1,
+                                            )
+                                            .and_then(|_| read_backup(&backup));
+                                            state.backup_status = Some(match restored {
+                                                Ok(entries) => {
+                                                    // Signed off work stays as it was submitted
+                                                    let (entries, _) = without_locked_entries(
+                                                        entries,
+                                                        period_submissions,
+                                                        &utc_offset,
+                                                    );
+                                                    time_sheet_entries.retain(|entry| {
+                                                        is_entry_locked(
+                                                            period_submissions,
+                                                            entry,
+                                                            &utc_offset,
+                                                        )
+                                                    });
+                                                    let kept = time_sheet_entries.len();
+                                                    time_sheet_entries.extend(entries);
+                                                    format!(
+                                                        "Restored {}, kept the {} entries in locked periods",
+                                                        backup.display(),
+                                                        kept
+                                                    )
+                                                }
+                                                Err(error) => error,
+                                            });
+                                            // The entries have moved around
+                                            state.entry_edit = None;
+                                            state.entry_split = None;
+                                            state.pending_delete = None;
+                                            ui.close_menu();
+                                        }
+                                    }
+                                });
+                                if let Some(backup_status) = &state.backup_status {
+                                    ui.label(backup_status);
+                                }
+                            });
+                        }
+                        ui.horizontal(|ui| match pin_lock {
+                            Some(_) => {
+                                ui.label("PIN set");
+                                if ui.button("Lock now").clicked() {
+                                    state.pin_gate.unlocked = false;
+                                }
+                                if ui.button("Remove PIN").clicked() {
+                                    *pin_lock = None;
+                                }
+                            }
+                            None => {
+                                ui.label("PIN");
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut state.new_pin)
+                                        .password(true)
+                                        .desired_width(80.0),
+                                );
+                                if ui
+                                    .add_enabled(
+                                        state.new_pin.trim().chars().count() >= PIN_MIN_LENGTH,
+                                        egui::Button::new("Set PIN"),
+                                    )
+                                    .on_hover_text(
+                                        "asked for at startup, the saved data isn't encrypted",
+                                    )
+                                    .on_disabled_hover_text(format!(
+                                        "use at least {} characters",
+                                        PIN_MIN_LENGTH
+                                    ))
+                                    .clicked()
+                                {
+                                    let salt = chrono::offset::Utc::now().timestamp_nanos() as u64;
+                                    *pin_lock = Some(PinLock::new(&state.new_pin, salt));
+                                    state.new_pin = String::new();
+                                    state.pin_gate.unlocked = true;
+                                }
+                            }
+                        });
+                        if ui.button("Clear all data").clicked() {
+                            state.clear_all_data = Some(ClearAllData::default());
+                        }
                     });
+                }
+            });
+        *side_panel_width = side_panel.response.rect.width();
 
-                    ui.text_edit_multiline(&mut state.manual_add_notes);
-                    let minutes = match state.manual_add_minutes.parse::<f32>() {
-                        Ok(mins) => mins,
-                        _error => 0.0,
+        if state.timers.is_empty() && !*compact_mode {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.heading("Timesheet Entries");
+                if let Some(last_deleted) = state.deleted.last() {
+                    let description = match last_deleted {
+                        Deleted::Entry(_, entry) => format!(
+                            "the {} entry on {}",
+                            entry.project_type,
+                            entry.work_start_datetime.with_timezone(&utc_offset).format("%F")
+                        ),
+                        Deleted::ProjectType(_, project_type) => {
+                            format!("the project type {}", project_type)
+                        }
                     };
-                    if !state.manual_add_project.is_empty()
-                        && minutes > 0.0
-                        && minutes < (24.0 * 60.0)
-                        && ui.button("Add").clicked()
-                        && !state.manual_add_project.is_empty()
-                        && !state.manual_add_minutes.is_empty()
+                    if ui
+                        .button("Undo delete")
+                        .on_hover_text(format!("Restores {}", description))
+                        .clicked()
                     {
-                        time_sheet_entries.push(TimeSheetEntry::from_minutes(
-                            &state.manual_add_project,
-                            minutes,
-                            &state.manual_add_notes,
-                            &state.manual_add_date,
-                        ));
+                        // Inserting shifts the indices the delete window points at
+                        state.pending_delete = None;
+                        match state.deleted.pop() {
+                            Some(Deleted::Entry(index, entry)) => {
+                                // Inserting shifts the index of the entry being edited
+                                state.entry_edit = None;
+                                state.entry_split = None;
+                                time_sheet_entries
+                                    .insert(index.min(time_sheet_entries.len()), entry)
+                            }
+                            Some(Deleted::ProjectType(index, project_type)) => {
+                                project_types.insert(index.min(project_types.len()), project_type)
+                            }
+                            None => {}
+                        }
                     }
-                });
-            }
-        });
-
-        if state.work_start_time.is_none() {
-            egui::CentralPanel::default().show(ctx, |ui| {
-                ui.heading("Timesheet Entries");
+                }
 
                 egui::CollapsingHeader::new("Time Sheet Entries").show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Filters");
+                        if ui
+                            .button("Reset filters")
+                            .on_hover_text("Back to every project, a year either side of today")
+                            .clicked()
+                        {
+                            state.previous_filters =
+                                Some(std::mem::take(&mut state.time_sheet_filters));
+                        }
+                        if state.previous_filters.is_some()
+                            && ui.button("Restore filters").clicked()
+                        {
+                            state.time_sheet_filters = state.previous_filters.take().unwrap();
+                        }
+                    });
                     let filters = &mut state.time_sheet_filters;
-                    ui.label("Filters");
                     ui.horizontal(|ui| {
                         ui.label("Project Name");
                         ui.text_edit_singleline(&mut filters.project_type);
+                        let picked_dates = (filters.start_date, filters.end_date);
+                        // The date picker only edits a Date<Utc>
+                        let mut start_date = Utc.from_utc_date(&filters.start_date);
+                        let mut end_date = Utc.from_utc_date(&filters.end_date);
                         ui.label("Start Date");
                         ui.add(
-                            DatePickerButton::new(&mut filters.start_date)
-                                .id_source("filter_start_date"),
+                            DatePickerButton::new(&mut start_date).id_source("filter_start_date"),
                         );
                         ui.label("End Date");
-                        ui.add(
-                            DatePickerButton::new(&mut filters.end_date)
-                                .id_source("filter_end_date"),
-                        );
+                        ui.add(DatePickerButton::new(&mut end_date).id_source("filter_end_date"));
+                        filters.start_date = start_date.naive_utc();
+                        filters.end_date = end_date.naive_utc();
                         if filters.start_date > filters.end_date {
                             std::mem::swap(&mut filters.start_date, &mut filters.end_date);
+                            state.filter_dates_swapped = true;
+                        } else if (filters.start_date, filters.end_date) != picked_dates {
+                            state.filter_dates_swapped = false;
+                        }
+                    });
+                    if state.filter_dates_swapped {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            "The start date must be on or before the end date, so they were swapped",
+                        );
+                    }
+                    ui.horizontal(|ui| {
+                        for range in QuickDateRange::ALL {
+                            if ui.button(range.label()).clicked() {
+                                let (start_date, end_date) = range.dates(
+                                    local_today(&utc_offset).naive_utc(),
+                                    *week_start,
+                                    state.time_sheet_summary_start_date.naive_utc(),
+                                    *summary_period_days,
+                                );
+                                filters.start_date = start_date;
+                                filters.end_date = end_date;
+                                state.filter_dates_swapped = false;
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Notes");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut filters.notes_search)
+                                .hint_text("search notes"),
+                        );
+                        ui.label("Tag");
+                        ui.add(egui::TextEdit::singleline(&mut filters.tag).desired_width(80.0));
+                        ui.checkbox(
+                            &mut filters.without_notes_only,
+                            "only entries without notes",
+                        );
+                        let without_notes_count = time_sheet_entries
+                            .iter()
+                            .filter(|entry| {
+                                filters.matches(entry, &utc_offset) && entry.notes.trim().is_empty()
+                            })
+                            .count();
+                        ui.label(format!("{} entries without notes", without_notes_count));
+                    });
+                    let filtered_entries: Vec<&TimeSheetEntry> = time_sheet_entries
+                        .iter()
+                        .filter(|entry| filters.matches(entry, &utc_offset))
+                        .collect();
+                    let filtered_total =
+                        filtered_entries
+                            .iter()
+                            .fold(Duration::zero(), |total, entry| {
+                                total + (entry.work_end_datetime - entry.work_start_datetime)
+                            });
+                    let filtered_weekday_totals = weekday_totals(
+                        filtered_entries.iter().copied(),
+                        *week_start,
+                        &utc_offset,
+                    );
+                    ui.horizontal(|ui| {
+                        #[cfg(not(target_arch = "wasm32"))]
+                        ui.text_edit_singleline(&mut state.export_path);
+                        if ui
+                            .button("Export CSV")
+                            .on_hover_text("Exports the filtered entries")
+                            .clicked()
+                        {
+                            state.export_status = Some(save_export(
+                                ui.ctx(),
+                                &state.export_path,
+                                csv_with_header(
+                                    &export_header(&report_labels.name, organization),
+                                    entries_to_csv(&filtered_entries),
+                                ),
+                            ));
+                        }
+                        if ui
+                            .button("Copy weekly CSV")
+                            .on_hover_text("Copies the filtered entries grouped by week")
+                            .clicked()
+                        {
+                            ui.output().copied_text = csv_with_header(
+                                &export_header(&report_labels.name, organization),
+                                entries_to_csv_by_week(&filtered_entries, *week_start, &utc_offset),
+                            );
+                        }
+                        if let Some(export_status) = &state.export_status {
+                            ui.label(export_status);
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        #[cfg(not(target_arch = "wasm32"))]
+                        ui.text_edit_singleline(&mut state.data_path);
+                        if ui
+                            .button("Export all entries")
+                            .on_hover_text("Saves every entry so it can be imported elsewhere")
+                            .clicked()
+                        {
+                            state.import_status = Some(match entries_to_ron(time_sheet_entries) {
+                                Ok(contents) => save_export(ui.ctx(), &state.data_path, contents),
+                                Err(error) => error,
+                            });
+                        }
+                        if ui
+                            .button("Import entries")
+                            .on_hover_text("Adds exported entries, skipping ones already here")
+                            .clicked()
+                        {
+                            state.import_status = Some(
+                                match read_import(&state.data_path, &state.import_text)
+                                    .and_then(|contents| entries_from_ron(&contents))
+                                {
+                                    Ok(imported) => {
+                                        let (imported, locked) = without_locked_entries(
+                                            imported,
+                                            period_submissions,
+                                            &utc_offset,
+                                        );
+                                        let (added, skipped) =
+                                            merge_entries(time_sheet_entries, imported);
+                                        state.import_text = String::new();
+                                        format!(
+                                            "Imported {} entries, skipped {} duplicates, {} skipped: locked period",
+                                            added, skipped, locked
+                                        )
+                                    }
+                                    Err(error) => error,
+                                },
+                            );
+                        }
+                        if let Some(import_status) = &state.import_status {
+                            ui.label(import_status);
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        #[cfg(not(target_arch = "wasm32"))]
+                        ui.text_edit_singleline(&mut state.csv_import_path);
+                        if ui
+                            .button("Read CSV")
+                            .on_hover_text("Reads a CSV from another tracker to match up its columns")
+                            .clicked()
+                        {
+                            match read_import(&state.csv_import_path, &state.import_text)
+                                .and_then(|contents| csv_header_and_rows(parse_csv(&contents)))
+                            {
+                                Ok((header, rows)) => {
+                                    state.csv_import = Some(CsvImport {
+                                        mapping: CsvColumnMapping::guess(&header),
+                                        header,
+                                        rows,
+                                    });
+                                    state.csv_import_errors = Vec::new();
+                                }
+                                Err(error) => state.import_status = Some(error),
+                            }
                         }
                     });
+                    let mut finished_csv_import = false;
+                    if let Some(csv_import) = &mut state.csv_import {
+                        ui.label(format!(
+                            "{} rows, pick the column for each part of an entry. Entries need a start and end, a start and duration, or a date and duration.",
+                            csv_import.rows.len()
+                        ));
+                        egui::Grid::new("csv_import_columns").show(ui, |ui| {
+                            let mapping = &mut csv_import.mapping;
+                            for (name, column) in [
+                                ("project", &mut mapping.project),
+                                ("start", &mut mapping.start),
+                                ("end", &mut mapping.end),
+                                ("date", &mut mapping.date),
+                                ("duration", &mut mapping.duration),
+                                ("notes", &mut mapping.notes),
+                            ] {
+                                ui.label(name);
+                                egui::ComboBox::from_id_source(("csv_import_column", name))
+                                    .selected_text(match column {
+                                        Some(index) => csv_import.header[*index].to_string(),
+                                        None => "none".to_string(),
+                                    })
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(column, None, "none");
+                                        for (index, column_name) in
+                                            csv_import.header.iter().enumerate()
+                                        {
+                                            ui.selectable_value(column, Some(index), column_name);
+                                        }
+                                    });
+                                ui.end_row();
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            if ui.button("Import CSV entries").clicked() {
+                                let (imported, errors) = entries_from_csv_rows(
+                                    &csv_import.rows,
+                                    &csv_import.mapping,
+                                    &utc_offset,
+                                );
+                                let (imported, locked) = without_locked_entries(
+                                    imported,
+                                    period_submissions,
+                                    &utc_offset,
+                                );
+                                let (added, skipped) = merge_entries(time_sheet_entries, imported);
+                                state.import_status = Some(format!(
+                                    "Imported {} entries, skipped {} duplicates and {} rows that couldn't be read, {} skipped: locked period",
+                                    added,
+                                    skipped,
+                                    errors.len(),
+                                    locked
+                                ));
+                                state.csv_import_errors = errors;
+                                state.import_text = String::new();
+                                finished_csv_import = true;
+                            }
+                            if ui.button("Cancel").clicked() {
+                                finished_csv_import = true;
+                            }
+                        });
+                    }
+                    if finished_csv_import {
+                        state.csv_import = None;
+                    }
+                    if !state.csv_import_errors.is_empty() {
+                        egui::CollapsingHeader::new(format!(
+                            "{} rows weren't imported",
+                            state.csv_import_errors.len()
+                        ))
+                        .id_source("csv_import_errors")
+                        .show(ui, |ui| {
+                            for error in state.csv_import_errors.iter() {
+                                ui.colored_label(egui::Color32::RED, error);
+                            }
+                            if ui.button("Dismiss").clicked() {
+                                state.csv_import_errors = Vec::new();
+                            }
+                        });
+                    }
+                    #[cfg(target_arch = "wasm32")]
+                    ui.add(
+                        egui::TextEdit::multiline(&mut state.import_text)
+                            .hint_text("paste exported entries or a CSV here to import them"),
+                    );
+                    ui.label(format!(
+                        "Filtered total: {}",
+                        duration_display.format(&filtered_total)
+                    ));
+                    egui::CollapsingHeader::new("Hours by weekday")
+                        .id_source("filtered_weekday_totals")
+                        .show(ui, |ui| {
+                            egui::Grid::new("weekday_totals_grid").show(ui, |ui| {
+                                for (weekday, _) in filtered_weekday_totals.iter() {
+                                    ui.label(weekday.to_string());
+                                }
+                                ui.end_row();
+                                for (_, total) in filtered_weekday_totals.iter() {
+                                    ui.label(duration_display.format(total));
+                                }
+                                ui.end_row();
+                            });
+                        });
+                    let uncategorized = uncategorized_projects(time_sheet_entries, project_types);
+                    if !uncategorized.is_empty() {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(
+                                egui::Color32::YELLOW,
+                                format!(
+                                    "Entries for deleted project types: {}",
+                                    uncategorized.join(", ")
+                                ),
+                            );
+                            egui::ComboBox::from_id_source("reassign_uncategorized")
+                                .selected_text(if state.reassign_project.is_empty() {
+                                    "move them to"
+                                } else {
+                                    &state.reassign_project
+                                })
+                                .show_ui(ui, |ui| {
+                                    for project_type in active_project_types.iter() {
+                                        ui.selectable_value(
+                                            &mut state.reassign_project,
+                                            project_type.to_string(),
+                                            project_type,
+                                        );
+                                    }
+                                });
+                            if ui
+                                .add_enabled(
+                                    project_types.contains(&state.reassign_project),
+                                    egui::Button::new("Reassign"),
+                                )
+                                .clicked()
+                            {
+                                let (_, locked) = reassign_uncategorized(
+                                    time_sheet_entries,
+                                    project_types,
+                                    &state.reassign_project,
+                                    period_submissions,
+                                    &utc_offset,
+                                );
+                                state.reassign_status = if locked > 0 {
+                                    Some(format!(
+                                        "{} entries in locked periods were left as they are",
+                                        locked
+                                    ))
+                                } else {
+                                    None
+                                };
+                            }
+                            if let Some(reassign_status) = &state.reassign_status {
+                                ui.label(reassign_status);
+                            }
+                        });
+                    }
                     egui::ScrollArea::new([false, true]).show(ui, |ui| {
+                        grid_density.apply(ui);
+                        let mut entry_action = None;
+                        let overlapping = overlapping_entries(time_sheet_entries);
                         show_timesheet_entries_grid(
                             ui,
                             time_sheet_entries,
-                            &mut entries_to_delete,
+                            &mut entry_action,
                             &state.time_sheet_filters,
+                            &mut state.entry_sort,
+                            period_submissions,
+                            project_unit_labels,
+                            project_colors,
+                            project_types,
+                            &overlapping,
+                            &utc_offset,
+                            *duration_display,
                         );
+                        match entry_action {
+                            Some(EntryAction::Delete(index)) => {
+                                state.pending_delete = Some(PendingDelete::Entry(
+                                    index,
+                                    time_sheet_entries[index].created_at,
+                                ));
+                            }
+                            Some(EntryAction::Edit(index)) => {
+                                state.entry_edit =
+                                    Some(EntryEdit::new(
+                                        index,
+                                        &time_sheet_entries[index],
+                                        &utc_offset,
+                                    ));
+                            }
+                            Some(EntryAction::Split(index)) => {
+                                state.entry_split =
+                                    Some(EntrySplit::new(
+                                        index,
+                                        &time_sheet_entries[index],
+                                        &utc_offset,
+                                    ));
+                            }
+                            Some(EntryAction::Duplicate(index)) => {
+                                let today = chrono::offset::Utc::now()
+                                    .with_timezone(&utc_offset)
+                                    .date_naive();
+                                if is_date_locked(period_submissions, today) {
+                                    state.duplicate_error =
+                                        Some("Today is in a locked period".to_string());
+                                } else {
+                                    let duplicate =
+                                        time_sheet_entries[index].duplicate_on(today, &utc_offset);
+                                    time_sheet_entries.push(duplicate);
+                                    state.duplicate_error = None;
+                                }
+                            }
+                            None => {}
+                        }
+                    });
+                    if let Some(error) = &state.duplicate_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+                    if let Some(entry_edit) = &mut state.entry_edit {
+                        let mut finished = false;
+                        ui.separator();
+                        ui.label("Edit entry");
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_source("edit_project_type")
+                                .selected_text(&entry_edit.project_type)
+                                .show_ui(ui, |ui| {
+                                    for project_type in project_types.iter() {
+                                        ui.selectable_value(
+                                            &mut entry_edit.project_type,
+                                            project_type.clone(),
+                                            project_type,
+                                        );
+                                    }
+                                });
+                            ui.label("start");
+                            ui.add(
+                                DatePickerButton::new(&mut entry_edit.start_date)
+                                    .id_source("edit_start_date"),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(&mut entry_edit.start_time)
+                                    .desired_width(50.0),
+                            );
+                            ui.label("end");
+                            ui.add(
+                                DatePickerButton::new(&mut entry_edit.end_date)
+                                    .id_source("edit_end_date"),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(&mut entry_edit.end_time)
+                                    .desired_width(50.0),
+                            );
+                        });
+                        ui.text_edit_multiline(&mut entry_edit.notes);
+                        ui.horizontal(|ui| {
+                            ui.label("tags");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut entry_edit.tags)
+                                    .hint_text("comma separated"),
+                            );
+                        });
+                        ui.checkbox(&mut entry_edit.billable, "billable");
+                        ui.horizontal(|ui| {
+                            if ui.button("save").clicked() {
+                                // The grid only offers edit outside locked periods, this keeps
+                                // the entry from being moved into one
+                                if is_date_locked(
+                                    period_submissions,
+                                    entry_edit.start_date.naive_utc(),
+                                ) {
+                                    entry_edit.error =
+                                        Some("that date is in a locked period".to_string());
+                                } else {
+                                    match time_sheet_entries.get_mut(entry_edit.index) {
+                                        Some(entry) => match entry_edit.apply(entry) {
+                                            Ok(()) => finished = true,
+                                            Err(error) => entry_edit.error = Some(error),
+                                        },
+                                        None => finished = true,
+                                    }
+                                }
+                            }
+                            if ui.button("cancel").clicked() {
+                                finished = true;
+                            }
+                            if let Some(error) = &entry_edit.error {
+                                ui.colored_label(egui::Color32::RED, error);
+                            }
+                        });
+                        if finished {
+                            state.entry_edit = None;
+                        }
+                    }
+                    if let Some(entry_split) = &mut state.entry_split {
+                        let mut finished = false;
+                        let mut split = false;
+                        ui.separator();
+                        ui.label("Split entry");
+                        ui.horizontal(|ui| {
+                            ui.label("at");
+                            ui.add(
+                                DatePickerButton::new(&mut entry_split.date)
+                                    .id_source("split_date"),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(&mut entry_split.time)
+                                    .desired_width(50.0),
+                            );
+                            ui.label("then");
+                            egui::ComboBox::from_id_source("split_project_type")
+                                .selected_text(&entry_split.project_type)
+                                .show_ui(ui, |ui| {
+                                    for project_type in project_types.iter() {
+                                        ui.selectable_value(
+                                            &mut entry_split.project_type,
+                                            project_type.clone(),
+                                            project_type,
+                                        );
+                                    }
+                                });
+                        });
+                        ui.horizontal(|ui| {
+                            if ui.button("split").clicked() {
+                                match entry_split.apply(time_sheet_entries) {
+                                    Ok(()) => {
+                                        finished = true;
+                                        split = true;
+                                    }
+                                    Err(error) => entry_split.error = Some(error),
+                                }
+                            }
+                            if ui.button("cancel").clicked() {
+                                finished = true;
+                            }
+                            if let Some(error) = &entry_split.error {
+                                ui.colored_label(egui::Color32::RED, error);
+                            }
+                        });
+                        if finished {
+                            state.entry_split = None;
+                        }
+                        if split {
+                            // The entries after the split one have moved along
+                            state.entry_edit = None;
+                            state.pending_delete = None;
+                        }
+                    }
+                });
+
+                egui::CollapsingHeader::new("Calendar").show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.button("<").clicked() {
+                            state.calendar_month = month_start(state.calendar_month, -1);
+                        }
+                        ui.label(state.calendar_month.format("%B %Y").to_string());
+                        if ui.button(">").clicked() {
+                            state.calendar_month = month_start(state.calendar_month, 1);
+                        }
+                    });
+                    if let Some(date) = show_calendar(
+                        ui,
+                        time_sheet_entries,
+                        state.calendar_month,
+                        *week_start,
+                        &utc_offset,
+                        excluded_from_totals,
+                    ) {
+                        state.time_sheet_filters.start_date = date;
+                        state.time_sheet_filters.end_date = date;
+                    }
+                });
+
+                egui::CollapsingHeader::new("All-time totals").show(ui, |ui| {
+                    egui::Grid::new("all_time_totals_grid").show(ui, |ui| {
+                        for (project, total) in project_totals(time_sheet_entries) {
+                            ui.label(project);
+                            ui.label(format_duration_hours(&total));
+                            ui.end_row();
+                        }
                     });
                 });
 
@@ -298,153 +3257,1402 @@ impl eframe::App for TemplateApp {
                             DatePickerButton::new(&mut state.time_sheet_summary_start_date)
                                 .id_source("Start_Date"),
                         );
+                        egui::ComboBox::from_id_source("summary_period_days")
+                            .selected_text(format!("{} days", summary_period_days))
+                            .show_ui(ui, |ui| {
+                                for days in SUMMARY_PERIOD_PRESETS {
+                                    ui.selectable_value(
+                                        summary_period_days,
+                                        days,
+                                        format!("{} days", days),
+                                    );
+                                }
+                            });
+                        ui.add(
+                            egui::DragValue::new(summary_period_days)
+                                .clamp_range(1..=366)
+                                .suffix(" days"),
+                        );
                         ui.label(format!(
                             "through {}",
-                            (state.time_sheet_summary_start_date + Duration::days(14)).format("%F")
+                            period_end_date(
+                                state.time_sheet_summary_start_date.naive_utc(),
+                                *summary_period_days
+                            )
+                            .format("%F")
                         ));
-                        if ui.button("Genereate Timesheet Summary").clicked() {
-                            let start_date = state.time_sheet_summary_start_date.naive_utc();
-                            let end_date = (state.time_sheet_summary_start_date
-                                + Duration::days(14))
-                            .naive_utc();
-                            state.time_sheet_summary = Some(TimeSheetSummary::new(
+                        ui.checkbox(&mut state.summary_by_tag, "by tag")
+                            .on_hover_text("entries with several tags count toward each of them");
+                        if ui
+                            .button("Generate Timesheet Summary")
+                            .on_hover_text("Ctrl+G")
+                            .clicked()
+                        {
+                            state.generate_time_sheet_summary(
                                 time_sheet_entries,
-                                &start_date,
-                                &end_date,
+                                *summary_period_days,
+                                &utc_offset,
+                                excluded_from_totals,
+                                project_types,
+                            );
+                        }
+                    });
+                    if let Some(error) = &state.time_sheet_summary_error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+                    let period_start = state.time_sheet_summary_start_date.naive_utc();
+                    let mut reopen_period = false;
+                    ui.horizontal(|ui| match period_submissions.get_mut(&period_start) {
+                        Some(submission) => {
+                            ui.label(format!(
+                                "Submitted on {}",
+                                submission.submitted_on.with_timezone(&utc_offset).format("%F %R")
                             ));
+                            if !submission.approved_by.is_empty() {
+                                ui.label(format!("approved by {}", submission.approved_by));
+                            }
+                            ui.checkbox(&mut submission.locked, "locked");
+                            if ui.button("reopen period").clicked() {
+                                reopen_period = true;
+                            }
+                        }
+                        None => {
+                            ui.label("Not submitted. Approved by");
+                            ui.text_edit_singleline(&mut state.period_approved_by);
+                            ui.checkbox(&mut state.lock_period_on_submit, "lock period");
+                            if ui.button("Mark submitted").clicked() {
+                                period_submissions.insert(
+                                    period_start,
+                                    PeriodSubmission {
+                                        period_end: period_end_date(
+                                            period_start,
+                                            *summary_period_days,
+                                        ),
+                                        approved_by: state.period_approved_by.trim().to_string(),
+                                        submitted_on: chrono::offset::Utc::now(),
+                                        locked: state.lock_period_on_submit,
+                                    },
+                                );
+                                state.period_approved_by = String::new();
+                            }
                         }
                     });
-                    show_timesheet_summary_grid(ui, &state.time_sheet_summary);
-                });
-
+                    if reopen_period {
+                        period_submissions.remove(&period_start);
+                    }
+                    if let Some(summary) = &state.time_sheet_summary {
+                        ui.horizontal(|ui| {
+                            if ui.button("Copy summary as HTML").clicked() {
+                                ui.output().copied_text = summary_to_html(
+                                    summary,
+                                    rounding,
+                                    report_labels,
+                                    &export_header(&report_labels.name, organization),
+                                );
+                            }
+                            if ui.button("Copy summary as Markdown").clicked() {
+                                ui.output().copied_text = summary_to_markdown(
+                                    summary,
+                                    rounding,
+                                    report_labels,
+                                    &export_header(&report_labels.name, organization),
+                                );
+                            }
+                            if ui.button("Copy daily comments CSV").clicked() {
+                                ui.output().copied_text = daily_comments_to_csv(summary);
+                            }
+                        });
+                        #[cfg(not(target_arch = "wasm32"))]
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut state.pdf_path);
+                            if ui
+                                .button("Export PDF")
+                                .on_hover_text("Saves the summary as a timesheet to sign")
+                                .clicked()
+                            {
+                                state.pdf_status = Some(save_export(
+                                    ui.ctx(),
+                                    &state.pdf_path,
+                                    summary_to_pdf(
+                                        summary,
+                                        rounding,
+                                        report_labels,
+                                        &export_header(&report_labels.name, organization),
+                                    ),
+                                ));
+                            }
+                            if let Some(pdf_status) = &state.pdf_status {
+                                ui.label(pdf_status);
+                            }
+                        });
+                        show_expected_hours_variance(
+                            ui,
+                            summary.total_hours_worked(),
+                            expected_period_hours(
+                                *weekly_hours,
+                                &summary.period_dates,
+                                working_days,
+                            ),
+                        );
+                        ui.label(format!(
+                            "Average per working day: {}",
+                            format_duration_hours(&summary.average_per_working_day(working_days))
+                        ));
+                        let missing_days: Vec<String> = summary
+                            .missing_working_days(working_days)
+                            .iter()
+                            .map(|date| date.format("%a %m/%d").to_string())
+                            .collect();
+                        if !missing_days.is_empty() {
+                            ui.label(format!(
+                                "Working days with nothing logged: {}",
+                                missing_days.join(", ")
+                            ));
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        ui.checkbox(color_summary_by_intensity, "color cells by hours");
+                        ui.checkbox(show_summary_notes_inline, "show notes in cells");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("columns");
+                        ui.radio_value(summary_date_format, SummaryDateFormat::Date, "date");
+                        ui.radio_value(summary_date_format, SummaryDateFormat::IsoWeek, "ISO week");
+                        ui.radio_value(summary_date_format, SummaryDateFormat::Both, "both");
+                    });
+                    ui.scope(|ui| {
+                        grid_density.apply(ui);
+                        show_timesheet_summary_grid(
+                            ui,
+                            &state.time_sheet_summary,
+                            rounding,
+                            *color_summary_by_intensity,
+                            *show_summary_notes_inline,
+                            *summary_date_format,
+                            hourly_rates,
+                            project_colors,
+                            *week_start,
+                        );
+                    });
+                    if let Some(summary) = &state.time_sheet_summary {
+                        show_summary_units(ui, summary, project_unit_labels);
+                        if !project_clients.is_empty() {
+                            egui::CollapsingHeader::new("By client").show(ui, |ui| {
+                                show_client_totals(
+                                    ui,
+                                    summary,
+                                    project_clients,
+                                    hourly_rates,
+                                    rounding,
+                                );
+                            });
+                        }
+                    }
+                    ui.checkbox(
+                        &mut state.show_split_preview,
+                        "show entries split across days",
+                    );
+                    if state.show_split_preview {
+                        show_split_entries_preview(
+                            ui,
+                            time_sheet_entries,
+                            &period_start,
+                            &period_end_date(period_start, *summary_period_days),
+                            &utc_offset,
+                        );
+                    }
+                });
+
+                egui::CollapsingHeader::new("Charts").show(ui, |ui| {
+                    match &state.time_sheet_summary {
+                        Some(summary) => {
+                            show_project_hours_chart(ui, summary, rounding, project_colors)
+                        }
+                        None => {
+                            ui.label("Generate a summary to chart its period");
+                        }
+                    }
+                });
+
                 egui::warn_if_debug_build(ui);
             });
         }
 
-        //TODO: does this actually work if entries_to_delete has more than 1 item?
-        //TODO: Does entries to delete need to be a vec, or can this just be a
-        // usize?
-        for idx in entries_to_delete.iter() {
-            time_sheet_entries.remove(*idx);
+        if state.timers.is_empty() {
+            // The timers were finished some other way, nothing is left to ask about
+            state.idle_since = None;
+        }
+        if let Some(idle_since) = state.idle_since {
+            egui::Window::new("Still working?")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "There's been no activity since {}, {} ago",
+                        idle_since.with_timezone(&utc_offset).format("%R"),
+                        format_duration(&(now - idle_since))
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button("Keep the time").clicked() {
+                            state.idle_since = None;
+                        }
+                        if ui.button("Discard the idle time").clicked() {
+                            for timer in state.timers.values_mut() {
+                                timer.discard_since(idle_since, now);
+                            }
+                            state.idle_since = None;
+                        }
+                        if ui.button("Stop timers when idling began").clicked() {
+                            let project_types: Vec<String> = state.timers.keys().cloned().collect();
+                            for project_type in project_types {
+                                let started_at = state.timers[&project_type].started_at;
+                                if let Some(entry) = state.finish_timer(
+                                    &project_type,
+                                    idle_since.max(started_at),
+                                    *snap_timer_to_quarter_hour,
+                                    entry_rounding,
+                                ) {
+                                    time_sheet_entries.push(entry);
+                                }
+                            }
+                            state.idle_since = None;
+                        }
+                    });
+                });
+        }
+
+        if let Some((project_type, next_project_type)) = state.long_finish.clone() {
+            match state
+                .timers
+                .get(&project_type)
+                .map(|timer| timer.elapsed(now))
+            {
+                Some(elapsed) => {
+                    // None keeps asking, Some(cap) finishes the timer with the entry no
+                    // longer than cap when there is one
+                    let mut finish: Option<Option<Duration>> = None;
+                    egui::Window::new("Finish this timer?")
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ctx, |ui| {
+                            ui.label(format!(
+                                "The {} timer has been running for {}. Is that right?",
+                                project_type,
+                                format_duration(&elapsed)
+                            ));
+                            ui.horizontal(|ui| {
+                                if ui.button("Yes, finish it").clicked() {
+                                    finish = Some(None);
+                                }
+                                if ui
+                                    .button(format!("Cap at {} hours", long_timer_hours))
+                                    .clicked()
+                                {
+                                    finish = Some(Some(Duration::hours(*long_timer_hours)));
+                                }
+                                if ui.button("Keep it running").clicked() {
+                                    state.long_finish = None;
+                                }
+                            });
+                        });
+                    if let Some(cap) = finish {
+                        let mut next_started_at = now;
+                        if let Some(mut entry) = state.finish_timer(
+                            &project_type,
+                            now,
+                            *snap_timer_to_quarter_hour,
+                            entry_rounding,
+                        ) {
+                            if let Some(cap) = cap {
+                                entry.work_end_datetime =
+                                    entry.work_end_datetime.min(entry.work_start_datetime + cap);
+                            }
+                            next_started_at = entry.work_end_datetime;
+                            time_sheet_entries.push(entry);
+                        }
+                        if let Some(next_project_type) = next_project_type {
+                            state.start_timer(&next_project_type, next_started_at);
+                            state.selected_project_type = Some(next_project_type);
+                        }
+                        state.long_finish = None;
+                    }
+                }
+                // The timer was finished some other way
+                None => state.long_finish = None,
+            }
+        }
+
+        if let Some(pending_delete) = state.pending_delete {
+            let description = match pending_delete {
+                PendingDelete::Entry(index, created_at) => time_sheet_entries
+                    .get(index)
+                    .filter(|entry| entry.created_at == created_at)
+                    .map(|entry| {
+                        format!(
+                            "Delete this entry?\n{} on {}",
+                            entry.project_type,
+                            entry
+                                .work_start_datetime
+                                .with_timezone(&utc_offset)
+                                .format("%F")
+                        )
+                    }),
+                PendingDelete::ProjectType(index) => project_types.get(index).map(|project_type| {
+                    let entry_count = time_sheet_entries
+                        .iter()
+                        .filter(|entry| &entry.project_type == project_type)
+                        .count();
+                    format!(
+                        "Delete the project type {}?\n{} entries use it, they will be kept",
+                        project_type, entry_count
+                    )
+                }),
+            };
+            match description {
+                Some(description) => {
+                    egui::Window::new("Confirm delete")
+                        .collapsible(false)
+                        .resizable(false)
+                        .show(ctx, |ui| {
+                            ui.label(description);
+                            ui.horizontal(|ui| {
+                                if ui.button("Confirm").clicked() {
+                                    match pending_delete {
+                                        PendingDelete::Entry(index, _) => {
+                                            entries_to_delete.push(index)
+                                        }
+                                        PendingDelete::ProjectType(index) => {
+                                            projects_to_delete.push(index)
+                                        }
+                                    }
+                                    state.pending_delete = None;
+                                }
+                                if ui.button("Cancel").clicked() {
+                                    state.pending_delete = None;
+                                }
+                            });
+                        });
+                }
+                // What it pointed at is already gone
+                None => state.pending_delete = None,
+            }
+        }
+
+        let mut cleared_all_data = false;
+        if let Some(clear_all_data) = &mut state.clear_all_data {
+            let mut finished = false;
+            egui::Window::new("Clear all data")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    if !clear_all_data.warned {
+                        ui.label(format!(
+                            "This permanently deletes all {} entries and the period sign-offs.",
+                            time_sheet_entries.len()
+                        ));
+                        ui.checkbox(
+                            &mut clear_all_data.clear_project_types,
+                            "clear the project types too",
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button("Continue").clicked() {
+                                clear_all_data.warned = true;
+                            }
+                            if ui.button("Cancel").clicked() {
+                                finished = true;
+                            }
+                        });
+                    } else {
+                        ui.label(format!(
+                            "Type {} to confirm. This can't be undone.",
+                            CLEAR_ALL_DATA_CONFIRMATION
+                        ));
+                        ui.text_edit_singleline(&mut clear_all_data.typed_confirmation);
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(
+                                    clear_all_data.typed_confirmation
+                                        == CLEAR_ALL_DATA_CONFIRMATION,
+                                    egui::Button::new("Clear all data"),
+                                )
+                                .clicked()
+                            {
+                                time_sheet_entries.clear();
+                                period_submissions.clear();
+                                if clear_all_data.clear_project_types {
+                                    project_types.clear();
+                                }
+                                cleared_all_data = true;
+                                finished = true;
+                            }
+                            if ui.button("Cancel").clicked() {
+                                finished = true;
+                            }
+                        });
+                    }
+                });
+            if finished {
+                state.clear_all_data = None;
+            }
+        }
+        if cleared_all_data {
+            // Nothing cleared should come back through undo, or through a form still
+            // pointing at an entry
+            state.deleted.clear();
+            state.entry_edit = None;
+            state.entry_split = None;
+            state.pending_delete = None;
+            state.time_sheet_summary = None;
+            entries_to_delete.clear();
+            projects_to_delete.clear();
+        }
+
+        if !entries_to_delete.is_empty() {
+            // The edited entry's index may no longer point at it
+            state.entry_edit = None;
+            state.entry_split = None;
+        }
+        let deleted_entries = remove_indices(time_sheet_entries, &entries_to_delete);
+        let deleted_project_types = remove_indices(project_types, &projects_to_delete);
+        state.deleted.extend(
+            deleted_entries
+                .into_iter()
+                .map(|(index, entry)| Deleted::Entry(index, entry)),
+        );
+        state.deleted.extend(
+            deleted_project_types
+                .into_iter()
+                .map(|(index, project_type)| Deleted::ProjectType(index, project_type)),
+        );
+        if state.deleted.len() > UNDO_LIMIT {
+            state.deleted.drain(..state.deleted.len() - UNDO_LIMIT);
+        }
+
+        if let Some(config_action) = self.state.config_action.take() {
+            self.run_config_action(ctx, config_action);
+        }
+
+        if cleared_all_data {
+            // Save straight away, so closing before the next autosave can't bring the
+            // data back
+            if let Some(storage) = frame.storage_mut() {
+                self.save(storage);
+                storage.flush();
+            }
+        }
+    }
+}
+
+fn show_today_breakdown(
+    ui: &mut Ui,
+    time_sheet_entries: &[TimeSheetEntry],
+    state: &State,
+    now: DateTime<Utc>,
+    utc_offset: &FixedOffset,
+    daily_goal_hours: f64,
+    excluded_from_totals: &HashSet<String>,
+) {
+    let local_now = now.with_timezone(utc_offset);
+    let today = local_now.date_naive();
+    let mut totals = TimeSheetSummary::new_in(time_sheet_entries, &today, &today, utc_offset)
+        .day_project_totals(&today);
+    if !state.timers.is_empty() {
+        // Only the part of the running sessions since midnight counts towards today
+        let since_midnight = local_now - local_now.date().and_hms(0, 0, 0);
+        for (project_type, timer) in state.timers.iter() {
+            let running = timer.elapsed(now).min(since_midnight);
+            match totals
+                .iter_mut()
+                .find(|(project, _)| project == project_type)
+            {
+                Some((_, total)) => *total = *total + running,
+                None => totals.push((project_type.to_string(), running)),
+            }
         }
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    }
+    if totals.is_empty() && daily_goal_hours <= 0.0 {
+        return;
+    }
 
-        for idx in projects_to_delete.iter() {
-            project_types.remove(*idx);
+    ui.add_space(10.0);
+    ui.label("Today so far");
+    egui::Grid::new("today_breakdown_grid").show(ui, |ui| {
+        for (project, total) in totals.iter() {
+            ui.label(project);
+            ui.label(format_duration_hours(total));
+            ui.end_row();
         }
+    });
+
+    if daily_goal_hours > 0.0 {
+        let today_total = totals
+            .iter()
+            .filter(|(project, _)| !excluded_from_totals.contains(project))
+            .fold(Duration::zero(), |total, (_, project_total)| {
+                total + *project_total
+            });
+        let worked_hours = today_total.num_seconds() as f64 / 3600.0;
+        let remaining_hours = daily_goal_hours - worked_hours;
+        let text = if remaining_hours > 0.0 {
+            format!(
+                "{:.2} of {:.2} hours, {:.2} to go",
+                worked_hours, daily_goal_hours, remaining_hours
+            )
+        } else {
+            format!(
+                "{:.2} of {:.2} hours, goal reached",
+                worked_hours, daily_goal_hours
+            )
+        };
+        ui.add(
+            egui::ProgressBar::new((worked_hours / daily_goal_hours).min(1.0) as f32).text(text),
+        );
+    }
+}
+
+fn show_expected_hours_variance(ui: &mut Ui, total_worked: Duration, expected_hours: f64) {
+    let worked_hours = total_worked.num_minutes() as f64 / 60.0;
+    let variance = worked_hours - expected_hours;
+    let text = format!(
+        "Period total {:.2} of {:.2} expected hours ({:+.2})",
+        worked_hours, expected_hours, variance
+    );
+    let text = egui::RichText::new(text).strong();
+    if variance.abs() > expected_hours * EXPECTED_HOURS_TOLERANCE {
+        ui.label(text.color(egui::Color32::RED));
+    } else {
+        ui.label(text);
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn show_timesheet_summary_grid<'a>(
     ui: &'a mut Ui,
     time_sheet_summary: &Option<TimeSheetSummary>,
+    rounding: &RoundingSettings,
+    color_by_intensity: bool,
+    notes_inline: bool,
+    date_format: SummaryDateFormat,
+    hourly_rates: &HashMap<String, f64>,
+    project_colors: &HashMap<String, [u8; 4]>,
+    week_start: Weekday,
 ) -> &'a mut Ui {
-    match time_sheet_summary {
-        Some(s) => {
-            if s.summary.keys().len() > 0 {
-                egui::Grid::new("Time_sheet_summary_grid").show(ui, |ui| {
-                    ui.label("project");
-                    for date in s.dates.iter() {
-                        ui.label(date.format("%m/%d").to_string());
+    if let Some(s) = time_sheet_summary {
+        if !s.summary.is_empty() {
+            let max_hours = s
+                .summary
+                .values()
+                .flat_map(|day| day.summary.values())
+                .map(|project| rounding.apply(project.hours_worked))
+                .max()
+                .unwrap_or_else(Duration::zero);
+            if date_format != SummaryDateFormat::Date {
+                if let Some(iso_weeks) = iso_weeks_label(&s.period_dates) {
+                    ui.strong(iso_weeks);
+                }
+            }
+            egui::Grid::new("Time_sheet_summary_grid").show(ui, |ui| {
+                ui.label("project");
+                for date in s.period_dates.iter() {
+                    ui.label(date_format.column_header(date));
+                }
+                ui.label("total");
+                ui.label("cost");
+                ui.end_row();
+                let mut total_date_times: HashMap<&NaiveDate, Duration> = HashMap::new();
+                for project in s.projects.iter() {
+                    let counts_in_totals = s.counts_in_totals(project);
+                    let project_label =
+                        ui.colored_label(project_color(project_colors, project), project);
+                    if !counts_in_totals {
+                        project_label.on_hover_text("not counted in the totals");
                     }
-                    ui.end_row();
-                    let mut total_date_times: HashMap<&NaiveDate, Duration> = HashMap::new();
-                    for project in s.projects.iter() {
-                        ui.label(project);
-                        for date in s.dates.iter() {
-                            let (hours, notes) = match s.summary.get(date) {
-                                Some(date_match) => match date_match.summary.get(project) {
-                                    Some(project_match) => (
-                                        project_match.hours_worked,
-                                        project_match.notes.to_string(),
-                                    ),
-                                    None => (Duration::zero(), "".to_string()),
-                                },
+                    let mut project_total = Duration::zero();
+                    for date in s.period_dates.iter() {
+                        let (hours, notes) = match s.summary.get(date) {
+                            Some(date_match) => match date_match.summary.get(project) {
+                                Some(project_match) => (
+                                    rounding.apply(project_match.hours_worked),
+                                    project_match.notes.to_string(),
+                                ),
                                 None => (Duration::zero(), "".to_string()),
-                            };
-                            let this_date_duration = match total_date_times.get(date) {
-                                Some(date_time) => *date_time,
-                                None => Duration::zero(),
-                            };
-                            let updated_time = this_date_duration + hours;
-                            total_date_times.insert(date, updated_time);
+                            },
+                            None => (Duration::zero(), "".to_string()),
+                        };
+                        let this_date_duration = match total_date_times.get(date) {
+                            Some(date_time) => *date_time,
+                            None => Duration::zero(),
+                        };
+                        if counts_in_totals {
+                            total_date_times.insert(date, this_date_duration + hours);
+                        }
+                        project_total = project_total + hours;
 
-                            if !notes.is_empty() {
-                                if ui.link(format_duration_hours(&hours)).hovered() {
-                                    egui::Window::new(format!("Notes for {}", date))
-                                        .fixed_pos(ui.next_widget_position())
-                                        .show(ui.ctx(), |ui| {
-                                            ui.label(notes.to_owned());
-                                        });
+                        let cell_color = if color_by_intensity {
+                            intensity_color(&hours, &max_hours)
+                        } else {
+                            egui::Color32::TRANSPARENT
+                        };
+                        egui::Frame::none().fill(cell_color).show(ui, |ui| {
+                            let hours_text = format_duration_hours(&hours);
+                            // Notes show beneath the hours, or on hover, and clicking any
+                            // cell copies its hours
+                            ui.vertical(|ui| {
+                                let response = if notes.is_empty() {
+                                    copyable_label(ui, &hours_text)
+                                } else if notes_inline {
+                                    let response = copyable_label(ui, &hours_text);
+                                    ui.small(&notes);
+                                    response
+                                } else {
+                                    // Tooltips are kept inside the screen, unlike a window
+                                    // pinned next to the cell
+                                    ui.link(&hours_text).on_hover_ui(|ui| {
+                                        ui.strong(format!("Notes for {}", date));
+                                        ui.label(&notes);
+                                    })
+                                };
+                                if response.clicked() {
+                                    ui.output().copied_text = hours_text;
                                 }
-                            } else {
+                            });
+                        });
+                    }
+                    ui.label(format_duration_hours(&project_total));
+                    match s.project_cost(project, hourly_rates, rounding) {
+                        Some(cost) => ui.label(format!("{:.2}", cost)),
+                        None => ui.label(""),
+                    };
+                    ui.end_row();
+                }
+                ui.separator();
+                for _ in s.period_dates.iter() {
+                    ui.separator();
+                }
+                ui.separator();
+                ui.separator();
+                ui.end_row();
+                ui.label("total");
+                for date in s.period_dates.iter() {
+                    // Dates are only filled in by the project rows above
+                    let total_hours = total_date_times
+                        .get(&date)
+                        .copied()
+                        .unwrap_or_else(Duration::zero);
+                    let hours_text = format_duration_hours(&total_hours);
+                    if copyable_label(ui, &hours_text).clicked() {
+                        ui.output().copied_text = hours_text;
+                    }
+                }
+                let period_total = total_date_times
+                    .values()
+                    .fold(Duration::zero(), |total, hours| total + *hours);
+                ui.label(format_duration_hours(&period_total));
+                ui.label(format!("{:.2}", s.total_cost(hourly_rates, rounding)));
+                ui.end_row();
+                ui.label("billable");
+                let mut billable_total = Duration::zero();
+                for date in s.period_dates.iter() {
+                    let billable_hours = s.billable_day_total(date, rounding);
+                    billable_total = billable_total + billable_hours;
+                    let hours_text = format_duration_hours(&billable_hours);
+                    if copyable_label(ui, &hours_text).clicked() {
+                        ui.output().copied_text = hours_text;
+                    }
+                }
+                ui.label(format_duration_hours(&billable_total));
+                ui.label("");
+                ui.end_row();
+                ui.label("week to date");
+                let week_to_date_totals = s.week_to_date_totals(week_start, rounding);
+                for (index, week_to_date) in week_to_date_totals.iter().enumerate() {
+                    let hours_text = format_duration_hours(week_to_date);
+                    // The last day of each week shows that week's subtotal
+                    let ends_week = s
+                        .period_dates
+                        .get(index + 1)
+                        .map_or(true, |next_date| next_date.weekday() == week_start);
+                    if ends_week {
+                        ui.strong(hours_text).on_hover_text("week total");
+                    } else {
+                        ui.weak(hours_text);
+                    }
+                }
+                ui.end_row();
+                // A subtotal row for each week, under that week's days, when there's more
+                // than one. The period total column stands in for a week total column, which
+                // would only hold one week's hours for each project.
+                let weeks = week_ranges(&s.period_dates, week_start);
+                if weeks.len() > 1 {
+                    for week in weeks {
+                        ui.label(format!(
+                            "week of {}",
+                            s.period_dates[week.start].format("%m/%d")
+                        ));
+                        for (index, date) in s.period_dates.iter().enumerate() {
+                            if week.contains(&index) {
+                                let hours = total_date_times
+                                    .get(&date)
+                                    .copied()
+                                    .unwrap_or_else(Duration::zero);
                                 ui.label(format_duration_hours(&hours));
+                            } else {
+                                ui.label("");
                             }
                         }
+                        ui.strong(format_duration_hours(&week_to_date_totals[week.end - 1]));
                         ui.end_row();
                     }
-                    ui.separator();
-                    for _ in s.dates.iter() {
-                        ui.separator();
-                    }
-                    ui.end_row();
-                    ui.label("total");
-                    for date in s.dates.iter() {
-                        let total_hours = total_date_times.get(&date).unwrap();
-                        ui.label(format_duration_hours(total_hours));
+                }
+            });
+        } else {
+            ui.label(no_hours_message(&s.period_dates));
+        }
+    }
+    ui
+}
+
+/// The positions in `period_dates` of each week, split before every `week_start`
+fn week_ranges(period_dates: &[NaiveDate], week_start: Weekday) -> Vec<std::ops::Range<usize>> {
+    let mut weeks = Vec::new();
+    let mut week_begins = 0;
+    for (index, date) in period_dates.iter().enumerate() {
+        if index > week_begins && date.weekday() == week_start {
+            weeks.push(week_begins..index);
+            week_begins = index;
+        }
+    }
+    if week_begins < period_dates.len() {
+        weeks.push(week_begins..period_dates.len());
+    }
+    weeks
+}
+
+/// Shown in place of the summary grid when nothing was logged in the period
+fn no_hours_message(period_dates: &[NaiveDate]) -> String {
+    match (period_dates.first(), period_dates.last()) {
+        (Some(start), Some(end)) if start != end => format!(
+            "No hours recorded for {} – {}",
+            start.format("%F"),
+            end.format("%F")
+        ),
+        (Some(date), _) => format!("No hours recorded for {}", date.format("%F")),
+        _ => "No hours recorded".to_string(),
+    }
+}
+
+/// A bar for each project's hours in the summary period, rounded like the summary grid
+fn show_project_hours_chart(
+    ui: &mut Ui,
+    summary: &TimeSheetSummary,
+    rounding: &RoundingSettings,
+    project_colors: &HashMap<String, [u8; 4]>,
+) {
+    let project_hours: Vec<(String, f64)> = summary
+        .projects
+        .iter()
+        .map(|project| {
+            let total = summary.project_total(project, rounding);
+            (project.to_string(), total.num_minutes() as f64 / 60.0)
+        })
+        .filter(|(_, hours)| *hours > 0.0)
+        .collect();
+    if project_hours.is_empty() {
+        ui.label("No data for this period");
+        return;
+    }
+
+    let bars = project_hours
+        .iter()
+        .enumerate()
+        .map(|(index, (project, hours))| {
+            Bar::new(index as f64, *hours)
+                .name(project)
+                .fill(project_color(project_colors, project))
+        })
+        .collect();
+    let chart = BarChart::new(bars).element_formatter(Box::new(|bar, _| {
+        format!("{}: {:.2} hours", bar.name, bar.value)
+    }));
+    let project_names: Vec<String> = project_hours
+        .into_iter()
+        .map(|(project, _)| project)
+        .collect();
+    Plot::new("project_hours_chart")
+        .height(200.0)
+        .include_y(0.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .show_x(false)
+        .show_y(false)
+        .x_axis_formatter(move |x, _| {
+            // Only label the whole numbers bars are drawn at
+            if x.fract() == 0.0 && x >= 0.0 {
+                project_names.get(x as usize).cloned().unwrap_or_default()
+            } else {
+                String::new()
+            }
+        })
+        .show(ui, |plot_ui| plot_ui.bar_chart(chart));
+}
+
+fn show_summary_units(
+    ui: &mut Ui,
+    time_sheet_summary: &TimeSheetSummary,
+    project_unit_labels: &HashMap<String, String>,
+) {
+    let mut project_units: Vec<(&String, &f64)> = time_sheet_summary.project_units.iter().collect();
+    if project_units.is_empty() {
+        return;
+    }
+    project_units.sort_by(|a, b| a.0.cmp(b.0));
+    let units: Vec<String> = project_units
+        .iter()
+        .map(|(project, units)| match project_unit_labels.get(*project) {
+            Some(unit_label) => format!("{}: {} {}", project, units, unit_label),
+            None => format!("{}: {}", project, units),
+        })
+        .collect();
+    ui.label(format!("Units: {}", units.join(", ")));
+}
+
+/// Hours and cost per client with each client's projects beneath it, the way invoices
+/// are laid out
+fn show_client_totals(
+    ui: &mut Ui,
+    time_sheet_summary: &TimeSheetSummary,
+    project_clients: &HashMap<String, String>,
+    hourly_rates: &HashMap<String, f64>,
+    rounding: &RoundingSettings,
+) {
+    let clients = time_sheet_summary.client_totals(project_clients, hourly_rates, rounding);
+    egui::Grid::new("client_totals_grid").show(ui, |ui| {
+        ui.label("client");
+        ui.label("hours");
+        ui.label("cost");
+        ui.end_row();
+        for client in clients.iter() {
+            ui.strong(client.client.as_deref().unwrap_or("no client"));
+            ui.strong(format_duration_hours(&client.hours));
+            ui.strong(format!("{:.2}", client.cost));
+            ui.end_row();
+            for (project, hours, cost) in client.projects.iter() {
+                ui.label(format!("    {}", project));
+                ui.label(format_duration_hours(hours));
+                match cost {
+                    Some(cost) => ui.label(format!("{:.2}", cost)),
+                    None => ui.label(""),
+                };
+                ui.end_row();
+            }
+        }
+    });
+}
+
+/// Writes an export to `path`. The web version can't write files, so the export is
+/// put on the clipboard instead.
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+fn save_export(ctx: &egui::Context, path: &str, contents: String) -> String {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        match std::fs::write(path, contents) {
+            Ok(()) => format!("Saved {}", path),
+            Err(error) => format!("Couldn't save {}: {}", path, error),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        ctx.output().copied_text = contents;
+        "Copied to the clipboard".to_string()
+    }
+}
+
+/// Reads an import from `path`. The web version can't read files, so it uses the
+/// `pasted` text instead.
+#[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+fn read_import(path: &str, pasted: &str) -> Result<String, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::read_to_string(path).map_err(|error| format!("Couldn't read {}: {}", path, error))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Ok(pasted.to_string())
+    }
+}
+
+fn copyable_label(ui: &mut Ui, text: &str) -> egui::Response {
+    ui.add(egui::Label::new(text).sense(egui::Sense::click()))
+        .on_hover_text("click to copy")
+}
+
+/// A month of days shaded by the hours worked on each, returns the day clicked
+fn show_calendar(
+    ui: &mut Ui,
+    time_sheet_entries: &[TimeSheetEntry],
+    calendar_month: NaiveDate,
+    week_start: Weekday,
+    utc_offset: &FixedOffset,
+    excluded_from_totals: &HashSet<String>,
+) -> Option<NaiveDate> {
+    let month_end = month_start(calendar_month, 1).pred();
+    let mut summary =
+        TimeSheetSummary::new_in(time_sheet_entries, &calendar_month, &month_end, utc_offset);
+    summary.excluded_from_totals = excluded_from_totals.clone();
+    let max_hours = summary
+        .period_dates
+        .iter()
+        .map(|date| summary.day_total(date))
+        .max()
+        .unwrap_or_else(Duration::zero);
+    let mut clicked_date = None;
+    egui::Grid::new("calendar_grid").show(ui, |ui| {
+        let mut weekday = week_start;
+        for _ in 0..7 {
+            ui.label(format!("{:?}", weekday));
+            weekday = weekday.succ();
+        }
+        ui.end_row();
+        let leading_days = (calendar_month.weekday().num_days_from_monday() + 7
+            - week_start.num_days_from_monday())
+            % 7;
+        for _ in 0..leading_days {
+            ui.label("");
+        }
+        for date in summary.period_dates.iter() {
+            let hours = summary.day_total(date);
+            egui::Frame::none()
+                .fill(intensity_color(&hours, &max_hours))
+                .show(ui, |ui| {
+                    if ui
+                        .selectable_label(false, date.day().to_string())
+                        .on_hover_text(format!("{} hours", format_duration_hours(&hours)))
+                        .clicked()
+                    {
+                        clicked_date = Some(*date);
                     }
                 });
+            if date.succ().weekday() == week_start {
+                ui.end_row();
             }
         }
-        None => {}
+    });
+    clicked_date
+}
+
+/// A dropdown of `recent_notes` that fills `notes` with the one picked
+fn show_recent_notes_menu(
+    ui: &mut Ui,
+    id_source: impl std::hash::Hash,
+    recent_notes: &[String],
+    notes: &mut String,
+) {
+    if recent_notes.is_empty() {
+        return;
     }
-    ui
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text("recent notes")
+        .show_ui(ui, |ui| {
+            for recent in recent_notes {
+                // Long notes are cut down to their first line in the list
+                let first_line = recent.lines().next().unwrap_or_default();
+                let label: String = first_line.chars().take(40).collect();
+                if ui
+                    .selectable_label(false, label)
+                    .on_hover_text(recent)
+                    .clicked()
+                {
+                    *notes = recent.to_string();
+                }
+            }
+        });
+}
+
+/// The color picked for `project`, or one picked from its name so every project has a
+/// stable color before any are set
+fn project_color(project_colors: &HashMap<String, [u8; 4]>, project: &str) -> egui::Color32 {
+    match project_colors.get(project) {
+        Some([r, g, b, a]) => egui::Color32::from_rgba_unmultiplied(*r, *g, *b, *a),
+        None => {
+            let hue = (stable_hash(project.as_bytes().iter()) % 1000) as f32 / 1000.0;
+            egui::color::Hsva::new(hue, 0.7, 0.85, 1.0).into()
+        }
+    }
+}
+
+/// Background for a summary cell, warmer the closer `hours` is to `max_hours`. Kept
+/// translucent so the cell text stays readable.
+fn intensity_color(hours: &Duration, max_hours: &Duration) -> egui::Color32 {
+    if *hours <= Duration::zero() || *max_hours <= Duration::zero() {
+        return egui::Color32::TRANSPARENT;
+    }
+    let intensity = (hours.num_seconds() as f32 / max_hours.num_seconds() as f32).min(1.0);
+    egui::Color32::from_rgba_unmultiplied(
+        255,
+        (200.0 - 130.0 * intensity) as u8,
+        40,
+        (30.0 + 110.0 * intensity) as u8,
+    )
+}
+
+fn show_split_entries_preview(
+    ui: &mut Ui,
+    time_sheet_entries: &[TimeSheetEntry],
+    start_date: &NaiveDate,
+    end_date: &NaiveDate,
+    utc_offset: &FixedOffset,
+) {
+    let mut any_split = false;
+    egui::Grid::new("split_entries_preview_grid").show(ui, |ui| {
+        for entry in time_sheet_entries.iter() {
+            let allocation = entry.split_at_midnight_in(utc_offset);
+            if allocation.len() < 2
+                || !allocation
+                    .iter()
+                    .any(|(date, _)| start_date <= date && date <= end_date)
+            {
+                continue;
+            }
+            any_split = true;
+            ui.label(&entry.project_type);
+            ui.label(format!(
+                "{} to {}",
+                entry
+                    .work_start_datetime
+                    .with_timezone(utc_offset)
+                    .format("%F %R"),
+                entry
+                    .work_end_datetime
+                    .with_timezone(utc_offset)
+                    .format("%F %R")
+            ));
+            let per_day: Vec<String> = allocation
+                .iter()
+                .map(|(date, hours)| {
+                    format!("{}: {}", date.format("%m/%d"), format_duration_hours(hours))
+                })
+                .collect();
+            ui.label(per_day.join(", "));
+            ui.end_row();
+        }
+    });
+    if !any_split {
+        ui.label("No entries in this period cross midnight");
+    }
+}
+
+/// Groups `indices` by the day each entry starts at `utc_offset`, keeping their order
+/// within each day. Days come oldest first unless `newest_first`.
+fn group_indices_by_day(
+    entries: &[TimeSheetEntry],
+    indices: &[usize],
+    utc_offset: &FixedOffset,
+    newest_first: bool,
+) -> Vec<(NaiveDate, Vec<usize>)> {
+    let mut days: BTreeMap<NaiveDate, Vec<usize>> = BTreeMap::new();
+    for index in indices {
+        let date = entries[*index]
+            .work_start_datetime
+            .with_timezone(utc_offset)
+            .date_naive();
+        days.entry(date).or_default().push(*index);
+    }
+    let mut days: Vec<(NaiveDate, Vec<usize>)> = days.into_iter().collect();
+    if newest_first {
+        days.reverse();
+    }
+    days
 }
 
+#[allow(clippy::too_many_arguments)]
 fn show_timesheet_entries_grid<'a>(
     ui: &'a mut Ui,
     time_sheet_entries: &[TimeSheetEntry],
-    entries_to_delete: &mut Vec<usize>,
+    entry_action: &mut Option<EntryAction>,
     filters: &TimeSheetEntryFilters,
+    entry_sort: &mut Option<EntrySort>,
+    period_submissions: &HashMap<NaiveDate, PeriodSubmission>,
+    project_unit_labels: &HashMap<String, String>,
+    project_colors: &HashMap<String, [u8; 4]>,
+    project_types: &[String],
+    overlapping: &HashSet<usize>,
+    utc_offset: &FixedOffset,
+    duration_display: DurationDisplay,
 ) -> &'a mut Ui {
-    egui::Grid::new("timesheet_entries_grid").show(ui, |ui| {
-        ui.label("project");
-        ui.label("start date");
-        ui.label("end date");
-        ui.label("elapsed time");
-        ui.label("notes");
-        ui.end_row();
-        for (index, entry) in time_sheet_entries.iter().enumerate() {
-            if !filters.project_type.is_empty()
-                && !entry
-                    .project_type
-                    .to_lowercase()
-                    .contains(&filters.project_type.to_lowercase())
-            {
-                continue;
-            }
-            if (filters.start_date > entry.work_start_datetime.date())
-                || (filters.end_date < entry.work_end_datetime.date())
+    let indices: Vec<usize> = sorted_entry_indices(time_sheet_entries, *entry_sort)
+        .into_iter()
+        .filter(|index| filters.matches(&time_sheet_entries[*index], utc_offset))
+        .collect();
+    ui.label(format!(
+        "Showing {} of {} entries",
+        indices.len(),
+        time_sheet_entries.len()
+    ));
+    if indices.is_empty() {
+        ui.label(if time_sheet_entries.is_empty() {
+            "No entries yet, start a timer or add one manually"
+        } else {
+            "No entries match your filters"
+        });
+        return ui;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("sort by");
+        for (column, title) in [
+            (EntrySortColumn::Project, "project"),
+            (EntrySortColumn::Start, "start"),
+            (EntrySortColumn::End, "end"),
+            (EntrySortColumn::Elapsed, "elapsed time"),
+        ] {
+            let sorted_ascending = entry_sort
+                .filter(|sort| sort.column == column)
+                .map(|sort| sort.ascending);
+            let header = match sorted_ascending {
+                Some(true) => format!("{} ⏶", title),
+                Some(false) => format!("{} ⏷", title),
+                None => title.to_string(),
+            };
+            if ui
+                .selectable_label(sorted_ascending.is_some(), header)
+                .on_hover_text("click to sort")
+                .clicked()
             {
-                continue;
+                // Clicking the sorted column again flips the direction
+                *entry_sort = Some(EntrySort {
+                    column,
+                    ascending: sorted_ascending != Some(true),
+                });
             }
+        }
+    });
 
-            ui.label(&entry.project_type);
-            ui.label(entry.work_start_datetime.format("%F").to_string());
-            ui.label(entry.work_end_datetime.format("%F").to_string());
-            let diff = entry.work_end_datetime - entry.work_start_datetime;
+    // Days follow the sort when it's by time, otherwise they're in date order
+    let newest_first = matches!(
+        entry_sort,
+        Some(EntrySort {
+            column: EntrySortColumn::Start | EntrySortColumn::End,
+            ascending: false,
+        })
+    );
+    let today = chrono::offset::Utc::now()
+        .with_timezone(utc_offset)
+        .date_naive();
+    for (date, day_indices) in
+        group_indices_by_day(time_sheet_entries, &indices, utc_offset, newest_first)
+    {
+        let day_total = day_indices.iter().fold(Duration::zero(), |total, index| {
+            let entry = &time_sheet_entries[*index];
+            total + (entry.work_end_datetime - entry.work_start_datetime)
+        });
+        egui::CollapsingHeader::new(format!(
+            "{}, {}",
+            date.format("%a %F"),
+            duration_display.format(&day_total)
+        ))
+        .id_source(("timesheet_entries_day", date))
+        .default_open(date == today)
+        .show(ui, |ui| {
+            egui::Grid::new(("timesheet_entries_grid", date)).show(ui, |ui| {
+                for title in [
+                    "project",
+                    "start",
+                    "end",
+                    "elapsed time",
+                    "units",
+                    "billable",
+                    "tags",
+                    "notes",
+                ] {
+                    ui.label(title);
+                }
+                ui.end_row();
+                for index in day_indices {
+                    show_timesheet_entry_row(
+                        ui,
+                        time_sheet_entries,
+                        index,
+                        date,
+                        entry_action,
+                        period_submissions,
+                        project_unit_labels,
+                        project_colors,
+                        project_types,
+                        overlapping,
+                        utc_offset,
+                        duration_display,
+                    );
+                }
+            });
+        });
+    }
+    ui
+}
 
-            ui.label(format_duration(&diff));
-            ui.label(&entry.notes);
-            if ui.button("delete").clicked() {
-                entries_to_delete.push(index);
-            }
-            ui.end_row();
+/// One row of a day's entries grid. Times are shown without the date when they fall on
+/// `date`, the day the row is grouped under.
+#[allow(clippy::too_many_arguments)]
+fn show_timesheet_entry_row(
+    ui: &mut Ui,
+    time_sheet_entries: &[TimeSheetEntry],
+    index: usize,
+    date: NaiveDate,
+    entry_action: &mut Option<EntryAction>,
+    period_submissions: &HashMap<NaiveDate, PeriodSubmission>,
+    project_unit_labels: &HashMap<String, String>,
+    project_colors: &HashMap<String, [u8; 4]>,
+    project_types: &[String],
+    overlapping: &HashSet<usize>,
+    utc_offset: &FixedOffset,
+    duration_display: DurationDisplay,
+) {
+    let entry = &time_sheet_entries[index];
+    let uncategorized = !project_types.contains(&entry.project_type);
+    let overlaps_another = overlapping.contains(&index);
+    let project_label = if overlaps_another {
+        egui::RichText::new(format!("⚠ {}", entry.project_type)).color(egui::Color32::RED)
+    } else if uncategorized {
+        egui::RichText::new(format!("? {}", entry.project_type)).color(egui::Color32::YELLOW)
+    } else {
+        egui::RichText::new(&entry.project_type)
+            .color(project_color(project_colors, &entry.project_type))
+    };
+    let mut hover_text = format!(
+        "created {}\nmodified {}",
+        entry.created_at.with_timezone(utc_offset).format("%F %T"),
+        entry.modified_at.with_timezone(utc_offset).format("%F %T")
+    );
+    if overlaps_another {
+        hover_text.push_str("\noverlaps another entry of this project");
+    }
+    if uncategorized {
+        hover_text.push_str("\nits project type was deleted");
+    }
+    ui.label(project_label).on_hover_text(hover_text);
+    for datetime in [entry.work_start_datetime, entry.work_end_datetime] {
+        let datetime = datetime.with_timezone(utc_offset);
+        if datetime.date_naive() == date {
+            ui.label(datetime.format("%R").to_string());
+        } else {
+            ui.label(datetime.format("%F %R").to_string());
+        }
+    }
+    let diff = entry.work_end_datetime - entry.work_start_datetime;
+
+    ui.label(duration_display.format(&diff));
+    match (entry.units, project_unit_labels.get(&entry.project_type)) {
+        (Some(units), Some(unit_label)) => ui.label(format!("{} {}", units, unit_label)),
+        (Some(units), None) => ui.label(units.to_string()),
+        (None, _) => ui.label(""),
+    };
+    ui.label(if entry.billable { "yes" } else { "no" });
+    ui.horizontal(|ui| {
+        for tag in entry.tags.iter() {
+            egui::Frame::none()
+                .fill(ui.visuals().faint_bg_color)
+                .rounding(4.0)
+                .inner_margin(egui::style::Margin::symmetric(4.0, 1.0))
+                .show(ui, |ui| ui.small(tag));
         }
     });
-    ui
+    ui.label(&entry.notes);
+    let locked = is_entry_locked(period_submissions, entry, utc_offset);
+    if ui
+        .add_enabled(!locked, egui::Button::new("delete"))
+        .on_disabled_hover_text("this entry is in a locked period")
+        .clicked()
+    {
+        *entry_action = Some(EntryAction::Delete(index));
+    }
+    if ui
+        .add_enabled(!locked, egui::Button::new("edit"))
+        .on_disabled_hover_text("this entry is in a locked period")
+        .clicked()
+    {
+        *entry_action = Some(EntryAction::Edit(index));
+    }
+    if ui
+        .add_enabled(!locked, egui::Button::new("split"))
+        .on_disabled_hover_text("this entry is in a locked period")
+        .clicked()
+    {
+        *entry_action = Some(EntryAction::Split(index));
+    }
+    if ui
+        .button("duplicate")
+        .on_hover_text("adds the same work for today")
+        .clicked()
+    {
+        *entry_action = Some(EntryAction::Duplicate(index));
+    }
+    ui.end_row();
+}
+
+/// The length a timer entry is stored with, after quarter hour snapping and then
+/// `entry_rounding`
+fn stored_duration(
+    elapsed: Duration,
+    snap_timer_to_quarter_hour: bool,
+    entry_rounding: &RoundingSettings,
+) -> Duration {
+    let elapsed = if snap_timer_to_quarter_hour {
+        snap_to_quarter_hour(elapsed)
+    } else {
+        elapsed
+    };
+    entry_rounding.apply_to_entry(elapsed)
+}
+
+/// The increment and direction pickers for `rounding`, `id` keeps several apart
+fn rounding_settings_ui(ui: &mut egui::Ui, id: &str, rounding: &mut RoundingSettings) {
+    egui::ComboBox::from_id_source((id, "increment"))
+        .selected_text(match rounding.increment_minutes {
+            0 => "off".to_string(),
+            minutes => format!("{} minutes", minutes),
+        })
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut rounding.increment_minutes, 0, "off");
+            for minutes in [6, 15, 30] {
+                ui.selectable_value(
+                    &mut rounding.increment_minutes,
+                    minutes,
+                    format!("{} minutes", minutes),
+                );
+            }
+        });
+    egui::ComboBox::from_id_source((id, "direction"))
+        .selected_text(rounding.direction.label())
+        .show_ui(ui, |ui| {
+            for direction in RoundingDirection::ALL {
+                ui.selectable_value(&mut rounding.direction, direction, direction.label());
+            }
+        });
+}
+
+/// The offset from UTC this machine is currently on
+fn local_utc_offset_minutes() -> i32 {
+    chrono::Local::now().offset().local_minus_utc() / 60
+}
+
+fn utc_offset_from_minutes(utc_offset_minutes: i32) -> FixedOffset {
+    FixedOffset::east_opt(utc_offset_minutes * 60).unwrap_or_else(|| FixedOffset::east(0))
+}
+
+/// Today's date at `utc_offset`, held as a `Date<Utc>` like the date pickers use
+fn local_today(utc_offset: &FixedOffset) -> Date<Utc> {
+    Utc.from_utc_date(
+        &chrono::offset::Utc::now()
+            .with_timezone(utc_offset)
+            .date_naive(),
+    )
 }
 
 fn format_duration(span: &chrono::Duration) -> String {
+    if *span < Duration::zero() {
+        return format!("-{}", format_duration(&-*span));
+    }
     if span.num_days() > 0 {
         return format!("{}d:{}h", span.num_days(), (span.num_hours() % 24));
     }
@@ -457,8 +4665,800 @@ fn format_duration(span: &chrono::Duration) -> String {
     format!("{}s", span.num_seconds())
 }
 
-fn format_duration_hours(span: &chrono::Duration) -> String {
-    let mut total_hours: f64 = span.num_minutes() as f64;
-    total_hours /= 60.0;
-    format!("{0:.2}", total_hours)
+/// Always HH:MM:SS, for the running timers where the seconds should keep counting
+fn format_stopwatch(span: &chrono::Duration) -> String {
+    let seconds = span.num_seconds().max(0);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Removes the items at `indices`, which refer to positions before any removal.
+/// Returns the removed items with their positions, in position order, so inserting them
+/// back in that order restores the list.
+fn remove_indices<T>(items: &mut Vec<T>, indices: &[usize]) -> Vec<(usize, T)> {
+    let mut indices = indices.to_vec();
+    // Remove from the back so earlier removals don't shift the later indices
+    indices.sort_unstable_by(|a, b| b.cmp(a));
+    indices.dedup();
+    let mut removed = Vec::new();
+    for idx in indices {
+        if idx < items.len() {
+            removed.push((idx, items.remove(idx)));
+        }
+    }
+    removed.reverse();
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimeSheet::{date_range, TimesheetDaySummary};
+
+    #[test]
+    fn test_timer_elapsed_leaves_out_pauses() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut state = TemplateApp::default().state;
+        state.start_timer("dev", start);
+        let timer = state.timers.get_mut("dev").unwrap();
+        timer.pause(start + Duration::minutes(20), true);
+        assert_eq!(
+            timer.elapsed(start + Duration::minutes(50)),
+            Duration::minutes(20)
+        );
+        timer.resume(start + Duration::minutes(50));
+        let entry = state
+            .finish_timer(
+                "dev",
+                start + Duration::minutes(60),
+                false,
+                &RoundingSettings::default(),
+            )
+            .unwrap();
+        assert_eq!(
+            entry.work_end_datetime - entry.work_start_datetime,
+            Duration::minutes(30)
+        );
+    }
+
+    #[test]
+    fn test_step_minutes() {
+        assert_eq!(step_minutes("", 15), "15");
+        assert_eq!(step_minutes("1:30", 30), "120");
+        assert_eq!(step_minutes("10", -15), "0");
+        assert_eq!(step_minutes("1430", 60), "1439");
+        assert_eq!(step_minutes("lots", 60), "60");
+    }
+
+    #[test]
+    fn test_week_ranges_split_on_week_start() {
+        // 2022-07-13 is a Wednesday
+        let dates = date_range(
+            &NaiveDate::from_ymd(2022, 7, 13),
+            &NaiveDate::from_ymd(2022, 7, 26),
+        );
+        assert_eq!(week_ranges(&dates, Weekday::Mon), vec![0..5, 5..12, 12..14]);
+        assert_eq!(week_ranges(&dates[..3], Weekday::Mon), vec![0..3]);
+        assert!(week_ranges(&[], Weekday::Mon).is_empty());
+    }
+
+    #[test]
+    fn test_no_hours_message_echoes_the_period() {
+        let start = NaiveDate::from_ymd(2022, 7, 1);
+        assert_eq!(
+            no_hours_message(&date_range(&start, &NaiveDate::from_ymd(2022, 7, 14))),
+            "No hours recorded for 2022-07-01 – 2022-07-14"
+        );
+        assert_eq!(
+            no_hours_message(&[start]),
+            "No hours recorded for 2022-07-01"
+        );
+    }
+
+    #[test]
+    fn test_summary_grid_without_projects_does_not_panic() {
+        let date = NaiveDate::from_ymd(2022, 7, 15);
+        let mut summary = HashMap::new();
+        summary.insert(
+            date,
+            TimesheetDaySummary {
+                summary: HashMap::new(),
+            },
+        );
+        let time_sheet_summary = Some(TimeSheetSummary {
+            summary,
+            projects: Vec::new(),
+            dates: vec![date],
+            period_dates: vec![date, date.succ()],
+            project_units: HashMap::new(),
+            excluded_from_totals: HashSet::new(),
+        });
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                show_timesheet_summary_grid(
+                    ui,
+                    &time_sheet_summary,
+                    &RoundingSettings::default(),
+                    false,
+                    false,
+                    SummaryDateFormat::Date,
+                    &HashMap::new(),
+                    &HashMap::new(),
+                    Weekday::Mon,
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn test_runs_longer_than_skips_stale_timers() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut timer = RunningTimer::new(start);
+        assert!(!timer.runs_longer_than(start + Duration::hours(12), 12));
+        assert!(timer.runs_longer_than(start + Duration::hours(13), 12));
+
+        // Paused time doesn't count toward the limit
+        timer.pause(start + Duration::hours(1), true);
+        assert!(!timer.runs_longer_than(start + Duration::hours(13), 12));
+
+        // Stale timers were already asked about when the app opened
+        let mut stale_timer = RunningTimer::new(start);
+        stale_timer.stale_since = Some(start + Duration::hours(2));
+        assert!(!stale_timer.runs_longer_than(start + Duration::hours(13), 12));
+    }
+
+    #[test]
+    fn test_pin_lock() {
+        let lock = PinLock::new("1234", 42);
+        assert!(lock.matches("1234"));
+        assert!(lock.matches(" 1234 "));
+        assert!(!lock.matches("4321"));
+        // A different salt keeps the same PIN from hashing the same
+        assert_ne!(PinLock::new("1234", 43).hash, lock.hash);
+    }
+
+    #[test]
+    fn test_pin_gate_locks_out_after_wrong_attempts() {
+        let lock = PinLock::new("1234", 42);
+        let now = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut pin_gate = PinGate::default();
+        for _ in 0..PIN_ATTEMPTS {
+            pin_gate.entry = "0000".to_string();
+            pin_gate.try_unlock(&lock, now);
+        }
+        assert!(!pin_gate.unlocked);
+
+        // The right PIN doesn't work until the lockout is over
+        pin_gate.entry = "1234".to_string();
+        pin_gate.try_unlock(&lock, now + Duration::seconds(PIN_LOCKOUT_SECONDS - 1));
+        assert!(!pin_gate.unlocked);
+        pin_gate.entry = "1234".to_string();
+        pin_gate.try_unlock(&lock, now + Duration::seconds(PIN_LOCKOUT_SECONDS));
+        assert!(pin_gate.unlocked);
+        assert_eq!(pin_gate.entry, "");
+    }
+
+    #[test]
+    fn test_pin_lockout_survives_a_restart() {
+        let lock = PinLock::new("1234", 42);
+        let now = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut pin_gate = PinGate {
+            entry: "0000".to_string(),
+            ..PinGate::default()
+        };
+        pin_gate.try_unlock(&lock, now);
+        let restored = StoredPinLockout::from(&pin_gate).restore();
+        assert_eq!(restored.failed_attempts, 1);
+        assert_eq!(restored.locked_until, None);
+
+        for _ in 1..PIN_ATTEMPTS {
+            pin_gate.entry = "0000".to_string();
+            pin_gate.try_unlock(&lock, now);
+        }
+        let mut restored = StoredPinLockout::from(&pin_gate).restore();
+        assert!(!restored.unlocked);
+        restored.entry = "1234".to_string();
+        restored.try_unlock(&lock, now + Duration::seconds(1));
+        assert!(!restored.unlocked);
+    }
+
+    #[test]
+    fn test_quick_date_ranges() {
+        // A Wednesday
+        let today = NaiveDate::from_ymd(2022, 7, 13);
+        let period_start = NaiveDate::from_ymd(2022, 6, 20);
+        let dates = |range: QuickDateRange| range.dates(today, Weekday::Mon, period_start, 14);
+        assert_eq!(dates(QuickDateRange::Today), (today, today));
+        assert_eq!(
+            dates(QuickDateRange::ThisWeek),
+            (
+                NaiveDate::from_ymd(2022, 7, 11),
+                NaiveDate::from_ymd(2022, 7, 17)
+            )
+        );
+        assert_eq!(
+            dates(QuickDateRange::LastWeek),
+            (
+                NaiveDate::from_ymd(2022, 7, 4),
+                NaiveDate::from_ymd(2022, 7, 10)
+            )
+        );
+        assert_eq!(
+            dates(QuickDateRange::ThisMonth),
+            (
+                NaiveDate::from_ymd(2022, 7, 1),
+                NaiveDate::from_ymd(2022, 7, 31)
+            )
+        );
+        assert_eq!(
+            dates(QuickDateRange::PayPeriod),
+            (
+                NaiveDate::from_ymd(2022, 7, 4),
+                NaiveDate::from_ymd(2022, 7, 17)
+            )
+        );
+        // Pay periods also repeat back before the summary start
+        assert_eq!(
+            QuickDateRange::PayPeriod.dates(
+                today,
+                Weekday::Mon,
+                NaiveDate::from_ymd(2022, 8, 1),
+                14
+            ),
+            (
+                NaiveDate::from_ymd(2022, 7, 4),
+                NaiveDate::from_ymd(2022, 7, 17)
+            )
+        );
+    }
+
+    #[test]
+    fn test_project_color_defaults_from_name() {
+        let mut project_colors = HashMap::new();
+        assert_eq!(
+            project_color(&project_colors, "dev"),
+            project_color(&project_colors, "dev")
+        );
+        assert_ne!(
+            project_color(&project_colors, "dev"),
+            project_color(&project_colors, "Meetings")
+        );
+        project_colors.insert("dev".to_string(), [10, 20, 30, 255]);
+        assert_eq!(
+            project_color(&project_colors, "dev"),
+            egui::Color32::from_rgb(10, 20, 30)
+        );
+    }
+
+    #[test]
+    fn test_lunch_is_only_excluded_without_a_save() {
+        assert!(TemplateApp::default()
+            .excluded_from_totals
+            .contains("Lunch"));
+        let stored_state: TemplateApp = ron::from_str("()").unwrap();
+        assert!(stored_state.excluded_from_totals.is_empty());
+    }
+
+    #[test]
+    fn test_stable_hash_is_fnv_1a() {
+        assert_eq!(stable_hash(b"".iter()), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(b"a".iter()), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_retroactive_end() {
+        let eastern = FixedOffset::west(5 * 3600);
+        // 09:00 local start, finished at 12:30 local
+        let started_at = Utc.ymd(2022, 7, 15).and_hms(14, 0, 0);
+        let now = Utc.ymd(2022, 7, 15).and_hms(17, 30, 0);
+        assert_eq!(
+            retroactive_end(started_at, "12:10", now, &eastern),
+            Ok(Utc.ymd(2022, 7, 15).and_hms(17, 10, 0))
+        );
+        assert_eq!(retroactive_end(started_at, "12:30", now, &eastern), Ok(now));
+        // Times before the start, or later than now and so yesterday, are rejected
+        assert!(retroactive_end(started_at, "08:45", now, &eastern).is_err());
+        assert!(retroactive_end(started_at, "13:00", now, &eastern).is_err());
+        assert!(retroactive_end(started_at, "noon", now, &eastern).is_err());
+
+        // A timer running over midnight can finish before midnight
+        let started_at = Utc.ymd(2022, 7, 16).and_hms(2, 0, 0);
+        let now = Utc.ymd(2022, 7, 16).and_hms(6, 0, 0);
+        assert_eq!(
+            retroactive_end(started_at, "23:15", now, &eastern),
+            Ok(Utc.ymd(2022, 7, 16).and_hms(4, 15, 0))
+        );
+    }
+
+    #[test]
+    fn test_next_cutoff_is_in_local_time() {
+        let cutoff = NaiveTime::from_hms(18, 0, 0);
+        let eastern = FixedOffset::west(5 * 3600);
+        // 13:00 local, so the cutoff is later the same day
+        let timer = RunningTimer::new(Utc.ymd(2022, 7, 15).and_hms(18, 0, 0));
+        assert_eq!(
+            timer.next_cutoff(cutoff, &eastern),
+            Utc.ymd(2022, 7, 15).and_hms(23, 0, 0)
+        );
+
+        // Started after the cutoff, so it's the next day's
+        let late_timer = RunningTimer::new(Utc.ymd(2022, 7, 15).and_hms(23, 30, 0));
+        assert_eq!(
+            late_timer.next_cutoff(cutoff, &eastern),
+            Utc.ymd(2022, 7, 16).and_hms(23, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_elapsed_leaves_out_only_earlier_pauses() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut timer = RunningTimer::new(start);
+        timer.pause(start + Duration::hours(1), true);
+        timer.resume(start + Duration::hours(2));
+        timer.pause(start + Duration::hours(3), true);
+        timer.resume(start + Duration::hours(4));
+        assert_eq!(
+            timer.elapsed(start + Duration::hours(5)),
+            Duration::hours(3)
+        );
+        // Finished earlier, a pause that began later isn't left out and one still going
+        // at the end is only left out up to then
+        assert_eq!(
+            timer.elapsed(start + Duration::hours(2) + Duration::minutes(30)),
+            Duration::hours(1) + Duration::minutes(30)
+        );
+        assert_eq!(
+            timer.elapsed(start + Duration::hours(1) + Duration::minutes(30)),
+            Duration::hours(1)
+        );
+    }
+
+    #[test]
+    fn test_discard_since_leaves_out_idle_time() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut timer = RunningTimer::new(start);
+        timer.discard_since(start + Duration::hours(1), start + Duration::hours(5));
+        assert_eq!(
+            timer.elapsed(start + Duration::hours(6)),
+            Duration::hours(2)
+        );
+
+        // Time already paused before the idle time began isn't left out twice
+        let mut paused_timer = RunningTimer::new(start);
+        paused_timer.pause(start + Duration::minutes(30), true);
+        paused_timer.discard_since(start + Duration::hours(1), start + Duration::hours(2));
+        assert_eq!(
+            paused_timer.elapsed(start + Duration::hours(2)),
+            Duration::minutes(30)
+        );
+        // and a timer paused by hand stays paused
+        assert_eq!(paused_timer.paused_at, Some(start + Duration::minutes(30)));
+        assert!(paused_timer.paused_manually);
+
+        // A timer paused by hand after the idle time began is paused from when it began
+        let mut late_paused_timer = RunningTimer::new(start);
+        late_paused_timer.pause(start + Duration::hours(2), true);
+        late_paused_timer.discard_since(start + Duration::hours(1), start + Duration::hours(3));
+        assert_eq!(
+            late_paused_timer.paused_at,
+            Some(start + Duration::hours(1))
+        );
+        assert_eq!(
+            late_paused_timer.elapsed(start + Duration::hours(4)),
+            Duration::hours(1)
+        );
+    }
+
+    #[test]
+    fn test_concurrent_timers_finish_separately() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut state = TemplateApp::default().state;
+        state.start_timer("On-call", start);
+        state.start_timer("dev", start + Duration::minutes(30));
+        // Starting a project that's already running keeps the original timer
+        state.start_timer("On-call", start + Duration::minutes(45));
+        let dev_entry = state
+            .finish_timer(
+                "dev",
+                start + Duration::minutes(90),
+                false,
+                &RoundingSettings::default(),
+            )
+            .unwrap();
+        assert_eq!(dev_entry.project_type, "dev");
+        assert_eq!(dev_entry.work_start_datetime, start + Duration::minutes(30));
+        assert_eq!(state.timers.len(), 1);
+        let on_call_entry = state
+            .finish_timer(
+                "On-call",
+                start + Duration::minutes(120),
+                false,
+                &RoundingSettings::default(),
+            )
+            .unwrap();
+        assert_eq!(on_call_entry.work_start_datetime, start);
+        assert!(state
+            .finish_timer("On-call", start, false, &RoundingSettings::default())
+            .is_none());
+        assert!(state.timers.is_empty());
+    }
+
+    #[test]
+    fn test_stored_running_timer_round_trip() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut timer = RunningTimer::new(start);
+        timer.pause(start + Duration::minutes(2), true);
+        timer.resume(start + Duration::minutes(5));
+        timer.pause(start + Duration::minutes(10), true);
+        timer.notes = "on call".to_string();
+        let stored = StoredRunningTimer::new("On-call", &timer, start + Duration::minutes(20));
+        let restored = stored.restore(start + Duration::hours(1)).unwrap();
+        assert_eq!(restored.started_at, start);
+        assert_eq!(restored.pauses, timer.pauses);
+        assert_eq!(restored.paused_at, Some(start + Duration::minutes(10)));
+        assert!(restored.paused_manually);
+        assert_eq!(restored.notes, "on call");
+        assert_eq!(restored.stale_since, None);
+    }
+
+    #[test]
+    fn test_stored_running_timer_from_long_ago_is_stale() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let timer = RunningTimer::new(start);
+        let saved_at = start + Duration::hours(3);
+        let stored = StoredRunningTimer::new("dev", &timer, saved_at);
+        let restored = stored.restore(start + Duration::days(2)).unwrap();
+        assert_eq!(restored.stale_since, Some(saved_at));
+        assert!(StoredRunningTimer::default().restore(start).is_none());
+    }
+
+    #[test]
+    fn test_stored_filters_round_trip() {
+        let filters = TimeSheetEntryFilters {
+            project_type: "dev".to_string(),
+            start_date: NaiveDate::from_ymd(2022, 7, 1),
+            end_date: NaiveDate::from_ymd(2022, 7, 31),
+            without_notes_only: true,
+            notes_search: "login".to_string(),
+            tag: "review".to_string(),
+        };
+        let restored = StoredTimeSheetEntryFilters::from(&filters).restore();
+        assert_eq!(restored.project_type, "dev");
+        assert_eq!(restored.start_date, NaiveDate::from_ymd(2022, 7, 1));
+        assert_eq!(restored.end_date, NaiveDate::from_ymd(2022, 7, 31));
+        assert!(restored.without_notes_only);
+        assert_eq!(restored.notes_search, "login");
+        assert_eq!(restored.tag, "review");
+
+        // Nothing stored yet falls back to the wide default range
+        let defaults = StoredTimeSheetEntryFilters::default().restore();
+        assert_eq!(
+            defaults.start_date,
+            TimeSheetEntryFilters::default().start_date
+        );
+    }
+
+    #[test]
+    fn test_filters_match_notes_search_ignoring_case() {
+        let start = chrono::offset::Utc::now();
+        let entry = TimeSheetEntry::new(
+            "dev",
+            start,
+            start + Duration::minutes(30),
+            "Fixed JIRA-123 login bug",
+        );
+        let mut filters = TimeSheetEntryFilters {
+            notes_search: "jira-123".to_string(),
+            ..Default::default()
+        };
+        assert!(filters.matches(&entry, &FixedOffset::east(0)));
+        filters.notes_search = "JIRA-124".to_string();
+        assert!(!filters.matches(&entry, &FixedOffset::east(0)));
+    }
+
+    #[test]
+    fn test_filters_match_local_dates() {
+        // 23:30 on the 15th at UTC-4 is 03:30 on the 16th in UTC
+        let eastern = FixedOffset::west(4 * 3600);
+        let start = Utc.ymd(2022, 7, 16).and_hms(3, 30, 0);
+        let entry = TimeSheetEntry::new("dev", start, start + Duration::minutes(20), "");
+        let mut filters = TimeSheetEntryFilters {
+            start_date: NaiveDate::from_ymd(2022, 7, 15),
+            end_date: NaiveDate::from_ymd(2022, 7, 15),
+            ..Default::default()
+        };
+        assert!(filters.matches(&entry, &eastern));
+        assert!(!filters.matches(&entry, &FixedOffset::east(0)));
+        filters.start_date = NaiveDate::from_ymd(2022, 7, 16);
+        filters.end_date = NaiveDate::from_ymd(2022, 7, 16);
+        assert!(!filters.matches(&entry, &eastern));
+    }
+
+    #[test]
+    fn test_clicked_calendar_day_keeps_its_entries() {
+        let eastern = FixedOffset::west(4 * 3600);
+        let start = Utc.ymd(2022, 7, 16).and_hms(3, 30, 0);
+        let entries = vec![TimeSheetEntry::new(
+            "dev",
+            start,
+            start + Duration::minutes(20),
+            "",
+        )];
+        let month = NaiveDate::from_ymd(2022, 7, 1);
+        let summary = TimeSheetSummary::new_in(
+            &entries,
+            &month,
+            &NaiveDate::from_ymd(2022, 7, 31),
+            &eastern,
+        );
+        let clicked = NaiveDate::from_ymd(2022, 7, 15);
+        assert_eq!(summary.day_total(&clicked), Duration::minutes(20));
+        // What clicking the day in show_calendar sets the filters to
+        let filters = TimeSheetEntryFilters {
+            start_date: clicked,
+            end_date: clicked,
+            ..Default::default()
+        };
+        assert!(entries.iter().all(|entry| filters.matches(entry, &eastern)));
+    }
+
+    #[test]
+    fn test_filters_match_tag_ignoring_case() {
+        let start = chrono::offset::Utc::now();
+        let mut entry = TimeSheetEntry::new("dev", start, start + Duration::minutes(30), "");
+        entry.tags = vec!["Review".to_string()];
+        let mut filters = TimeSheetEntryFilters {
+            tag: "review".to_string(),
+            ..Default::default()
+        };
+        assert!(filters.matches(&entry, &FixedOffset::east(0)));
+        // Tags match whole, unlike the notes search
+        filters.tag = "rev".to_string();
+        assert!(!filters.matches(&entry, &FixedOffset::east(0)));
+    }
+
+    #[test]
+    fn test_group_indices_by_day_keeps_order_within_days() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let entries: Vec<TimeSheetEntry> = [0, 26, 2, 24]
+            .iter()
+            .map(|hours| {
+                let work_start = start + Duration::hours(*hours);
+                TimeSheetEntry::new("dev", work_start, work_start + Duration::hours(1), "")
+            })
+            .collect();
+        let utc = FixedOffset::east(0);
+        assert_eq!(
+            group_indices_by_day(&entries, &[2, 1, 0, 3], &utc, false),
+            vec![
+                (NaiveDate::from_ymd(2022, 7, 15), vec![2, 0]),
+                (NaiveDate::from_ymd(2022, 7, 16), vec![1, 3]),
+            ]
+        );
+        let newest_first = group_indices_by_day(&entries, &[0, 1], &utc, true);
+        assert_eq!(newest_first[0].0, NaiveDate::from_ymd(2022, 7, 16));
+
+        // Days are taken at the offset, 09:00 UTC is still the 14th in Hawaii
+        let hawaii = FixedOffset::west(10 * 3600);
+        let days = group_indices_by_day(&entries, &[0], &hawaii, false);
+        assert_eq!(days[0].0, NaiveDate::from_ymd(2022, 7, 14));
+    }
+
+    #[test]
+    fn test_sorted_entry_indices() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let entries = vec![
+            TimeSheetEntry::new("b", start, start + Duration::minutes(30), ""),
+            TimeSheetEntry::new(
+                "a",
+                start + Duration::hours(2),
+                start + Duration::hours(3),
+                "",
+            ),
+            TimeSheetEntry::new("c", start - Duration::hours(1), start, ""),
+        ];
+        assert_eq!(sorted_entry_indices(&entries, None), vec![0, 1, 2]);
+        let by_project = EntrySort {
+            column: EntrySortColumn::Project,
+            ascending: true,
+        };
+        assert_eq!(
+            sorted_entry_indices(&entries, Some(by_project)),
+            vec![1, 0, 2]
+        );
+        let by_start_descending = EntrySort {
+            column: EntrySortColumn::Start,
+            ascending: false,
+        };
+        assert_eq!(
+            sorted_entry_indices(&entries, Some(by_start_descending)),
+            vec![1, 0, 2]
+        );
+        let by_elapsed = EntrySort {
+            column: EntrySortColumn::Elapsed,
+            ascending: true,
+        };
+        assert_eq!(
+            sorted_entry_indices(&entries, Some(by_elapsed)),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn test_format_duration_negative() {
+        assert_eq!(format_duration(&Duration::minutes(-90)), "-1h:30m");
+        assert_eq!(format_duration(&Duration::days(-2)), "-2d:0h");
+        assert_eq!(format_duration(&Duration::minutes(90)), "1h:30m");
+    }
+
+    #[test]
+    fn test_apply_config_merges_project_types_and_rates() {
+        let mut exporting = TemplateApp {
+            project_types: vec!["dev".to_string(), "Meetings".to_string()],
+            weekly_hours: 32.0,
+            ..Default::default()
+        };
+        exporting.hourly_rates.insert("dev".to_string(), 90.0);
+        let config: AppConfig =
+            ron::from_str(&ron::ser::to_string(&exporting.config()).unwrap()).unwrap();
+
+        let mut importing = TemplateApp::default();
+        importing.hourly_rates.insert("dev".to_string(), 60.0);
+        importing.hourly_rates.insert("Lunch".to_string(), 0.0);
+        importing.apply_config(config);
+        assert_eq!(importing.project_types, vec!["Lunch", "Meetings", "dev"]);
+        assert_eq!(importing.hourly_rates["dev"], 90.0);
+        assert_eq!(importing.hourly_rates["Lunch"], 0.0);
+        assert_eq!(importing.weekly_hours, 32.0);
+    }
+
+    #[test]
+    fn test_summary_date_format_iso_week() {
+        // 2022-08-09 is the Tuesday of ISO week 32
+        let date = NaiveDate::from_ymd(2022, 8, 9);
+        assert_eq!(SummaryDateFormat::Date.column_header(&date), "08/09\nTue");
+        assert_eq!(
+            SummaryDateFormat::IsoWeek.column_header(&date),
+            "W32-2\nTue"
+        );
+        assert_eq!(SummaryDateFormat::Both.column_header(&date), "08/09\nW32-2");
+        assert_eq!(iso_weeks_label(&[date]), Some("ISO week 32".to_string()));
+        assert_eq!(
+            iso_weeks_label(&[date, date + Duration::days(7)]),
+            Some("ISO weeks 32-33".to_string())
+        );
+        assert_eq!(iso_weeks_label(&[]), None);
+    }
+
+    #[test]
+    fn test_duration_display() {
+        let span = Duration::minutes(90);
+        assert_eq!(DurationDisplay::Clock.format(&span), "1h:30m");
+        assert_eq!(DurationDisplay::Decimal.format(&span), "1.50h");
+    }
+
+    #[test]
+    fn test_format_stopwatch() {
+        assert_eq!(format_stopwatch(&Duration::seconds(5)), "00:00:05");
+        assert_eq!(format_stopwatch(&Duration::seconds(3723)), "01:02:03");
+        assert_eq!(format_stopwatch(&Duration::hours(26)), "26:00:00");
+    }
+
+    #[test]
+    fn test_remove_indices_multiple_entries() {
+        let start = chrono::offset::Utc::now();
+        let mut entries: Vec<TimeSheetEntry> = (0..5)
+            .map(|index| {
+                TimeSheetEntry::new(
+                    &format!("project {}", index),
+                    start,
+                    start + Duration::minutes(30),
+                    "",
+                )
+            })
+            .collect();
+        remove_indices(&mut entries, &[1, 3]);
+        let remaining: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry.project_type.as_str())
+            .collect();
+        assert_eq!(remaining, vec!["project 0", "project 2", "project 4"]);
+    }
+
+    #[test]
+    fn test_entry_edit_apply() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut entry = TimeSheetEntry::new("dev", start, start + Duration::hours(1), "");
+        let mut entry_edit = EntryEdit::new(0, &entry, &FixedOffset::east(0));
+        entry_edit.project_type = "Meetings".to_string();
+        entry_edit.end_time = "11:30".to_string();
+        entry_edit.notes = "standup".to_string();
+        assert_eq!(entry_edit.apply(&mut entry), Ok(()));
+        assert_eq!(entry.project_type, "Meetings");
+        assert_eq!(
+            entry.work_end_datetime,
+            Utc.ymd(2022, 7, 15).and_hms(11, 30, 0)
+        );
+        assert_eq!(entry.notes, "standup");
+    }
+
+    #[test]
+    fn test_entry_edit_uses_the_utc_offset_and_keeps_seconds() {
+        let utc_offset = FixedOffset::west(7 * 3600);
+        let start = Utc.ymd(2022, 7, 15).and_hms(16, 0, 42);
+        let end = Utc.ymd(2022, 7, 15).and_hms(17, 30, 15);
+        let mut entry = TimeSheetEntry::new("dev", start, end, "");
+        let mut entry_edit = EntryEdit::new(0, &entry, &utc_offset);
+        assert_eq!(entry_edit.start_date, Utc.ymd(2022, 7, 15));
+        assert_eq!(entry_edit.start_time, "09:00");
+        assert_eq!(entry_edit.end_time, "10:30");
+
+        entry_edit.notes = "standup".to_string();
+        assert_eq!(entry_edit.apply(&mut entry), Ok(()));
+        assert_eq!(entry.work_start_datetime, start);
+        assert_eq!(entry.work_end_datetime, end);
+
+        entry_edit.end_time = "11:00".to_string();
+        assert_eq!(entry_edit.apply(&mut entry), Ok(()));
+        assert_eq!(entry.work_start_datetime, start);
+        assert_eq!(
+            entry.work_end_datetime,
+            Utc.ymd(2022, 7, 15).and_hms(18, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_entry_split_uses_the_utc_offset() {
+        let utc_offset = FixedOffset::east(2 * 3600);
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut entries = vec![TimeSheetEntry::new(
+            "dev",
+            start,
+            start + Duration::hours(2),
+            "",
+        )];
+        let mut entry_split = EntrySplit::new(0, &entries[0], &utc_offset);
+        assert_eq!(entry_split.time, "12:00");
+        entry_split.time = "11:30".to_string();
+        assert_eq!(entry_split.apply(&mut entries), Ok(()));
+        assert_eq!(
+            entries[0].work_end_datetime,
+            Utc.ymd(2022, 7, 15).and_hms(9, 30, 0)
+        );
+    }
+
+    #[test]
+    fn test_entry_edit_rejects_end_before_start() {
+        let start = Utc.ymd(2022, 7, 15).and_hms(9, 0, 0);
+        let mut entry = TimeSheetEntry::new("dev", start, start + Duration::hours(1), "");
+        let mut entry_edit = EntryEdit::new(0, &entry, &FixedOffset::east(0));
+        entry_edit.end_time = "08:00".to_string();
+        assert!(entry_edit.apply(&mut entry).is_err());
+        entry_edit.end_time = "soon".to_string();
+        assert!(entry_edit.apply(&mut entry).is_err());
+        assert_eq!(entry.work_end_datetime, start + Duration::hours(1));
+    }
+
+    #[test]
+    fn test_remove_indices_unsorted_and_duplicated() {
+        let mut project_types = vec!["a", "b", "c", "d"];
+        remove_indices(&mut project_types, &[2, 0, 2]);
+        assert_eq!(project_types, vec!["b", "d"]);
+    }
+
+    #[test]
+    fn test_remove_indices_returns_removed_for_undo() {
+        let mut project_types = vec!["a", "b", "c", "d"];
+        let removed = remove_indices(&mut project_types, &[3, 1]);
+        assert_eq!(removed, vec![(1, "b"), (3, "d")]);
+        for (index, project_type) in removed {
+            project_types.insert(index, project_type);
+        }
+        assert_eq!(project_types, vec!["a", "b", "c", "d"]);
+    }
 }
@@ -0,0 +1,140 @@
+// Timestamped copies of the entries kept next to the app's own storage, in case a save
+// is ever lost or corrupted
+
+use crate::export::{entries_from_ron, entries_to_ron};
+use crate::TimeSheet::TimeSheetEntry;
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+
+const BACKUP_PREFIX: &str = "entries-";
+const BACKUP_EXTENSION: &str = "ron";
+// The name main.rs runs the app under, which eframe keeps its storage under
+const APP_NAME: &str = "timetracking";
+
+/// Where the backups in `backup_dir` go. A relative path is taken from the data directory
+/// eframe saves the app's state in, not from wherever the app was started.
+pub fn resolve_backup_dir(backup_dir: &str) -> PathBuf {
+    let backup_dir = Path::new(backup_dir.trim());
+    if backup_dir.is_absolute() {
+        return backup_dir.to_path_buf();
+    }
+    match directories_next::ProjectDirs::from("", "", APP_NAME) {
+        Some(project_dirs) => project_dirs.data_dir().join(backup_dir),
+        None => backup_dir.to_path_buf(),
+    }
+}
+
+/// Backups in `dir`, newest first. The names hold the time they were written so sorting
+/// them sorts by age.
+pub fn list_backups(dir: &Path) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(read_dir) => read_dir
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                let name = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or("");
+                name.starts_with(BACKUP_PREFIX)
+                    && path.extension().and_then(|extension| extension.to_str())
+                        == Some(BACKUP_EXTENSION)
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    backups.sort();
+    backups.reverse();
+    backups
+}
+
+/// Writes the entries to a new backup in `dir` and removes all but the newest `keep`.
+/// Nothing is written when the newest backup already holds the same entries.
+pub fn write_backup(
+    dir: &Path,
+    entries: &[TimeSheetEntry],
+    now: DateTime<Utc>,
+    keep: usize,
+) -> Result<Option<PathBuf>, String> {
+    let contents = entries_to_ron(entries)?;
+    let backups = list_backups(dir);
+    if let Some(newest) = backups.first() {
+        if std::fs::read_to_string(newest).ok().as_deref() == Some(contents.as_str()) {
+            return Ok(None);
+        }
+    }
+    std::fs::create_dir_all(dir)
+        .map_err(|error| format!("Couldn't create {}: {}", dir.display(), error))?;
+    let path = dir.join(format!(
+        "{}{}.{}",
+        BACKUP_PREFIX,
+        now.format("%Y%m%d-%H%M%S"),
+        BACKUP_EXTENSION
+    ));
+    std::fs::write(&path, contents)
+        .map_err(|error| format!("Couldn't save {}: {}", path.display(), error))?;
+    for old_backup in list_backups(dir).iter().skip(keep.max(1)) {
+        // A backup that can't be removed is only taking up space
+        let _ = std::fs::remove_file(old_backup);
+    }
+    Ok(Some(path))
+}
+
+pub fn read_backup(path: &Path) -> Result<Vec<TimeSheetEntry>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|error| format!("Couldn't read {}: {}", path.display(), error))?;
+    entries_from_ron(&contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimeSheet::datetime_from_ymd_hms;
+    use chrono::Duration;
+
+    #[test]
+    fn test_write_backup_keeps_the_newest() {
+        let dir =
+            std::env::temp_dir().join(format!("timesheet-backup-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let now = datetime_from_ymd_hms(2022, 7, 1, 9, 0, 0);
+        let mut entries = Vec::new();
+        for minutes in 0..4 {
+            entries.push(TimeSheetEntry::new(
+                "dev",
+                now,
+                now + Duration::minutes(30),
+                "",
+            ));
+            let written =
+                write_backup(&dir, &entries, now + Duration::minutes(minutes), 2).unwrap();
+            assert!(written.is_some());
+        }
+        // Unchanged entries don't push out an older backup
+        assert_eq!(
+            write_backup(&dir, &entries, now + Duration::minutes(10), 2).unwrap(),
+            None
+        );
+
+        let backups = list_backups(&dir);
+        assert_eq!(
+            backups
+                .iter()
+                .map(|path| path.file_name().unwrap().to_str().unwrap())
+                .collect::<Vec<&str>>(),
+            vec!["entries-20220701-090300.ron", "entries-20220701-090200.ron"]
+        );
+        assert_eq!(read_backup(&backups[0]).unwrap().len(), 4);
+        assert_eq!(read_backup(&backups[1]).unwrap().len(), 3);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_backup_dir_keeps_absolute_paths() {
+        let dir = std::env::temp_dir().join("backups");
+        assert_eq!(resolve_backup_dir(dir.to_str().unwrap()), dir);
+        // A relative path doesn't depend on where the app was started
+        let resolved = resolve_backup_dir("backups");
+        assert!(resolved.ends_with("backups"));
+        assert!(resolved.is_absolute() || resolved == Path::new("backups"));
+    }
+}
@@ -0,0 +1,529 @@
+use crate::TimeSheet::{
+    format_duration_hours, week_start_date, RoundingSettings, TimeSheetEntry, TimeSheetSummary,
+};
+use chrono::{Duration, FixedOffset, NaiveDate, Weekday};
+
+/// Optional text added to summary reports, blank values are left out
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ReportLabels {
+    pub title: String,
+    pub name: String,
+    pub footer: String,
+}
+
+/// Lines identifying who an export is from, blank values are left out
+pub fn export_header(user_name: &str, organization: &str) -> Vec<String> {
+    [user_name, organization]
+        .iter()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+        .collect()
+}
+
+/// `csv` with a single field row for each line of `header` before it
+pub fn csv_with_header(header: &[String], csv: String) -> String {
+    let mut lines: Vec<String> = header.iter().map(|line| csv_field(line)).collect();
+    lines.push(csv);
+    lines.join("\n")
+}
+
+/// `markdown` with a paragraph for each line of `header` before it
+pub fn markdown_with_header(header: &[String], markdown: String) -> String {
+    let mut paragraphs: Vec<String> = header.iter().map(|line| markdown_escape(line)).collect();
+    paragraphs.push(markdown);
+    paragraphs.join("\n\n")
+}
+
+const CSV_HEADER: &str = "project_type,work_start_datetime,work_end_datetime,elapsed_minutes,notes";
+
+/// Quotes a CSV field if it contains anything that would break the row apart
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_entry_row(entry: &TimeSheetEntry) -> String {
+    format!(
+        "{},{},{},{},{}",
+        csv_field(&entry.project_type),
+        entry.work_start_datetime.to_rfc3339(),
+        entry.work_end_datetime.to_rfc3339(),
+        (entry.work_end_datetime - entry.work_start_datetime).num_minutes(),
+        csv_field(&entry.notes)
+    )
+}
+
+pub fn entries_to_csv(entries: &[&TimeSheetEntry]) -> String {
+    let mut lines = vec![CSV_HEADER.to_string()];
+    lines.extend(entries.iter().map(|entry| csv_entry_row(entry)));
+    lines.join("\n")
+}
+
+/// Entries in start order, grouped under a header row for each week with a subtotal
+/// row after each week and a grand total at the end. Entries go in the week of the day
+/// they start on at `utc_offset`, like the summary.
+pub fn entries_to_csv_by_week(
+    entries: &[&TimeSheetEntry],
+    week_start: Weekday,
+    utc_offset: &FixedOffset,
+) -> String {
+    let mut sorted_entries = entries.to_vec();
+    sorted_entries.sort_by_key(|entry| entry.work_start_datetime);
+
+    let mut lines = vec![CSV_HEADER.to_string()];
+    let mut current_week = None;
+    let mut week_total = Duration::zero();
+    let mut grand_total = Duration::zero();
+    for entry in sorted_entries {
+        let week = week_start_date(
+            entry
+                .work_start_datetime
+                .with_timezone(utc_offset)
+                .date_naive(),
+            week_start,
+        );
+        if current_week != Some(week) {
+            if current_week.is_some() {
+                lines.push(format!("Subtotal,,,{},", week_total.num_minutes()));
+            }
+            lines.push(format!("Week of {},,,,", week.format("%F")));
+            current_week = Some(week);
+            week_total = Duration::zero();
+        }
+        let elapsed = entry.work_end_datetime - entry.work_start_datetime;
+        week_total = week_total + elapsed;
+        grand_total = grand_total + elapsed;
+        lines.push(csv_entry_row(entry));
+    }
+    if current_week.is_some() {
+        lines.push(format!("Subtotal,,,{},", week_total.num_minutes()));
+    }
+    lines.push(format!("Total,,,{},", grand_total.num_minutes()));
+    lines.join("\n")
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The project by date summary matrix as a standalone HTML table, rounded like the
+/// Markdown and PDF exports, under the labels' title and the `header` lines
+pub fn summary_to_html(
+    summary: &TimeSheetSummary,
+    rounding: &RoundingSettings,
+    labels: &ReportLabels,
+    header: &[String],
+) -> String {
+    let mut html = String::new();
+    if !labels.title.is_empty() {
+        html.push_str(&format!("<h1>{}</h1>\n", html_escape(&labels.title)));
+    }
+    for line in header {
+        html.push_str(&format!("<p>{}</p>\n", html_escape(line)));
+    }
+    html.push_str("<table>\n<tr><th>project</th>");
+    for date in summary.period_dates.iter() {
+        html.push_str(&format!("<th>{}</th>", date.format("%m/%d")));
+    }
+    html.push_str("<th>total</th></tr>\n");
+    let (rows, day_totals) = rounded_summary_rows(summary, rounding);
+    for (project, cells) in rows.iter() {
+        html.push_str(&format!("<tr><td>{}</td>", html_escape(project)));
+        for worked in cells.iter() {
+            html.push_str(&format!("<td>{}</td>", format_duration_hours(worked)));
+        }
+        html.push_str(&format!(
+            "<td>{}</td></tr>\n",
+            format_duration_hours(&sum_durations(cells))
+        ));
+    }
+    html.push_str("<tr><th>total</th>");
+    for day_total in day_totals.iter() {
+        html.push_str(&format!("<th>{}</th>", format_duration_hours(day_total)));
+    }
+    html.push_str(&format!(
+        "<th>{}</th></tr>\n</table>\n",
+        format_duration_hours(&sum_durations(&day_totals))
+    ));
+    if !labels.footer.is_empty() {
+        html.push_str(&format!("<p>{}</p>\n", html_escape(&labels.footer)));
+    }
+    html
+}
+
+/// Each project's cells of the summary matrix rounded the same way as the summary grid,
+/// and the day totals of the projects that count in the totals
+pub fn rounded_summary_rows(
+    summary: &TimeSheetSummary,
+    rounding: &RoundingSettings,
+) -> (Vec<(String, Vec<Duration>)>, Vec<Duration>) {
+    let mut rows: Vec<(String, Vec<Duration>)> = Vec::new();
+    let mut day_totals = vec![Duration::zero(); summary.period_dates.len()];
+    for project in summary.projects.iter() {
+        let mut cells = Vec::new();
+        for (day_total, date) in day_totals.iter_mut().zip(summary.period_dates.iter()) {
+            let worked = rounding.apply(summary.hours_worked(date, project));
+            if summary.counts_in_totals(project) {
+                *day_total = *day_total + worked;
+            }
+            cells.push(worked);
+        }
+        rows.push((project.to_string(), cells));
+    }
+    (rows, day_totals)
+}
+
+fn sum_durations(durations: &[Duration]) -> Duration {
+    durations
+        .iter()
+        .fold(Duration::zero(), |total, duration| total + *duration)
+}
+
+/// The project by date summary matrix as a GitHub flavored Markdown table, with each
+/// cell rounded the same way as the summary grid. Days without work show 0.00. The
+/// labels' title comes first and their footer last.
+pub fn summary_to_markdown(
+    summary: &TimeSheetSummary,
+    rounding: &RoundingSettings,
+    labels: &ReportLabels,
+    header: &[String],
+) -> String {
+    let mut header_row = vec!["project".to_string()];
+    header_row.extend(
+        summary
+            .period_dates
+            .iter()
+            .map(|date| date.format("%m/%d").to_string()),
+    );
+    header_row.push("total".to_string());
+    let mut rows = vec![
+        header_row,
+        vec!["---".to_string(); summary.period_dates.len() + 2],
+    ];
+    let (project_rows, day_totals) = rounded_summary_rows(summary, rounding);
+    for (project, cells) in project_rows.iter() {
+        let mut row = vec![markdown_escape(project)];
+        row.extend(cells.iter().map(format_duration_hours));
+        row.push(format_duration_hours(&sum_durations(cells)));
+        rows.push(row);
+    }
+    let mut totals_row = vec!["**total**".to_string()];
+    totals_row.extend(day_totals.iter().map(format_duration_hours));
+    totals_row.push(format_duration_hours(&sum_durations(&day_totals)));
+    rows.push(totals_row);
+    let table = rows
+        .iter()
+        .map(|row| format!("| {} |", row.join(" | ")))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let mut markdown = markdown_with_header(header, table);
+    if !labels.title.is_empty() {
+        markdown = format!("# {}\n\n{}", markdown_escape(&labels.title), markdown);
+    }
+    if !labels.footer.is_empty() {
+        markdown.push_str(&format!("\n\n{}", markdown_escape(&labels.footer)));
+    }
+    markdown
+}
+
+fn markdown_escape(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+/// One comment per day in the period, made from every project's notes that day
+/// prefixed with the project name
+pub fn daily_comments(summary: &TimeSheetSummary) -> Vec<(NaiveDate, String)> {
+    summary
+        .period_dates
+        .iter()
+        .map(|date| {
+            let mut project_notes: Vec<(&String, String)> = match summary.summary.get(date) {
+                Some(day) => day
+                    .summary
+                    .iter()
+                    .filter_map(|(project, project_summary)| {
+                        let notes: Vec<&str> = project_summary
+                            .notes
+                            .lines()
+                            .map(str::trim)
+                            .filter(|line| !line.is_empty())
+                            .collect();
+                        if notes.is_empty() {
+                            None
+                        } else {
+                            Some((project, notes.join("; ")))
+                        }
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+            project_notes.sort();
+            let comment: Vec<String> = project_notes
+                .iter()
+                .map(|(project, notes)| format!("{}: {}", project, notes))
+                .collect();
+            (*date, comment.join(" | "))
+        })
+        .collect()
+}
+
+/// Every entry, in the format eframe already uses to persist them
+pub fn entries_to_ron(entries: &[TimeSheetEntry]) -> Result<String, String> {
+    ron::ser::to_string_pretty(entries, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+}
+
+pub fn entries_from_ron(input: &str) -> Result<Vec<TimeSheetEntry>, String> {
+    ron::from_str(input).map_err(|error| format!("Couldn't read the entries: {}", error))
+}
+
+pub fn daily_comments_to_csv(summary: &TimeSheetSummary) -> String {
+    let mut lines = vec!["date,comment".to_string()];
+    for (date, comment) in daily_comments(summary) {
+        lines.push(format!("{},{}", date.format("%F"), csv_field(&comment)));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TimeSheet::{datetime_from_ymd_hms, RoundingDirection};
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_entries_to_csv() {
+        let entries = [TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 15, 10, 30, 0),
+            "fixed a bug, then\nreviewed",
+        )];
+        let entry_refs: Vec<&TimeSheetEntry> = entries.iter().collect();
+        assert_eq!(
+            entries_to_csv(&entry_refs),
+            format!(
+                "{}\ndev,2022-07-15T09:00:00+00:00,2022-07-15T10:30:00+00:00,90,\"fixed a bug, then\nreviewed\"",
+                CSV_HEADER
+            )
+        );
+    }
+
+    #[test]
+    fn test_export_header_leaves_out_blank_lines() {
+        assert!(export_header("", " ").is_empty());
+        let header = export_header("Sam Jones", "Acme, Inc.");
+        assert_eq!(header, vec!["Sam Jones", "Acme, Inc."]);
+        assert_eq!(
+            csv_with_header(&header, CSV_HEADER.to_string()),
+            format!("Sam Jones\n\"Acme, Inc.\"\n{}", CSV_HEADER)
+        );
+        assert_eq!(
+            markdown_with_header(&export_header("Sam Jones", ""), "| a |".to_string()),
+            "Sam Jones\n\n| a |"
+        );
+        assert_eq!(csv_with_header(&[], CSV_HEADER.to_string()), CSV_HEADER);
+    }
+
+    #[test]
+    fn test_entries_to_csv_by_week_subtotals() {
+        // 2022-07-15 is a Friday, 2022-07-18 the following Monday
+        let entries = [
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 15, 10, 0, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 18, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 18, 9, 30, 0),
+                "",
+            ),
+            TimeSheetEntry::new(
+                "meetings",
+                datetime_from_ymd_hms(2022, 7, 14, 13, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 14, 13, 15, 0),
+                "standup",
+            ),
+        ];
+        let entry_refs: Vec<&TimeSheetEntry> = entries.iter().collect();
+        let csv = entries_to_csv_by_week(&entry_refs, Weekday::Mon, &FixedOffset::east(0));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "Week of 2022-07-11,,,,");
+        assert!(lines[2].starts_with("meetings,2022-07-14"));
+        assert!(lines[3].starts_with("dev,2022-07-15"));
+        assert_eq!(lines[4], "Subtotal,,,75,");
+        assert_eq!(lines[5], "Week of 2022-07-18,,,,");
+        assert!(lines[6].starts_with("dev,2022-07-18"));
+        assert_eq!(lines[7], "Subtotal,,,30,");
+        assert_eq!(lines[8], "Total,,,105,");
+
+        // 02:00 UTC on Monday is still Sunday evening at UTC-4
+        let late_entry = TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 18, 2, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 18, 3, 0, 0),
+            "",
+        );
+        let csv =
+            entries_to_csv_by_week(&[&late_entry], Weekday::Mon, &FixedOffset::west(4 * 3600));
+        assert_eq!(csv.lines().nth(1), Some("Week of 2022-07-11,,,,"));
+    }
+
+    #[test]
+    fn test_summary_to_html_includes_labels() {
+        let entries = [TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 15, 10, 30, 0),
+            "",
+        )];
+        let date = NaiveDate::from_ymd(2022, 7, 15);
+        let summary = TimeSheetSummary::new(&entries, &date, &date);
+        let labels = ReportLabels {
+            title: "July timesheet".to_string(),
+            name: "Pat & Co".to_string(),
+            footer: "Thanks for your business".to_string(),
+        };
+        let rounding = RoundingSettings {
+            increment_minutes: 60,
+            direction: RoundingDirection::Up,
+        };
+        let header = export_header(&labels.name, "Acme");
+        let html = summary_to_html(&summary, &rounding, &labels, &header);
+        assert!(html.contains("<h1>July timesheet</h1>"));
+        assert!(html.contains("<p>Pat &amp; Co</p>\n<p>Acme</p>"));
+        assert!(html.contains("Thanks for your business"));
+        // Rounded like the Markdown and PDF exports
+        assert!(html.contains("<td>2.00</td>"));
+        assert!(!html.contains("1.50"));
+
+        let unlabelled = summary_to_html(
+            &summary,
+            &RoundingSettings::default(),
+            &ReportLabels::default(),
+            &[],
+        );
+        assert!(unlabelled.contains("<td>1.50</td>"));
+        assert!(!unlabelled.contains("<h1>"));
+        assert!(!unlabelled.contains("<p>"));
+    }
+
+    #[test]
+    fn test_summary_to_markdown() {
+        let entries = [TimeSheetEntry::new(
+            "dev | ops",
+            datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 15, 10, 20, 0),
+            "",
+        )];
+        let start_date = NaiveDate::from_ymd(2022, 7, 15);
+        let end_date = NaiveDate::from_ymd(2022, 7, 16);
+        let summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        let rounding = RoundingSettings {
+            increment_minutes: 15,
+            direction: RoundingDirection::Up,
+        };
+        let table = "| project | 07/15 | 07/16 | total |\n\
+                     | --- | --- | --- | --- |\n\
+                     | dev \\| ops | 1.50 | 0.00 | 1.50 |\n\
+                     | **total** | 1.50 | 0.00 | 1.50 |";
+        assert_eq!(
+            summary_to_markdown(&summary, &rounding, &ReportLabels::default(), &[]),
+            table
+        );
+
+        let labels = ReportLabels {
+            title: "July timesheet".to_string(),
+            name: "Pat".to_string(),
+            footer: "Thanks for your business".to_string(),
+        };
+        assert_eq!(
+            summary_to_markdown(
+                &summary,
+                &rounding,
+                &labels,
+                &export_header(&labels.name, "Acme")
+            ),
+            format!(
+                "# July timesheet\n\nPat\n\nAcme\n\n{}\n\nThanks for your business",
+                table
+            )
+        );
+    }
+
+    #[test]
+    fn test_daily_comments_joins_projects_for_a_day() {
+        let entries = [
+            TimeSheetEntry::new(
+                "meetings",
+                datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 15, 9, 15, 0),
+                "standup",
+            ),
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 15, 10, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 15, 12, 0, 0),
+                "fixed the build",
+            ),
+            TimeSheetEntry::new(
+                "dev",
+                datetime_from_ymd_hms(2022, 7, 15, 13, 0, 0),
+                datetime_from_ymd_hms(2022, 7, 15, 14, 0, 0),
+                "code review",
+            ),
+        ];
+        let start_date = NaiveDate::from_ymd(2022, 7, 15);
+        let end_date = NaiveDate::from_ymd(2022, 7, 16);
+        let summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        let comments = daily_comments(&summary);
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].0, start_date);
+        assert_eq!(
+            comments[0].1,
+            "dev: fixed the build; code review | meetings: standup"
+        );
+        assert_eq!(comments[1], (end_date, String::new()));
+
+        let csv = daily_comments_to_csv(&summary);
+        assert_eq!(
+            csv,
+            "date,comment\n2022-07-15,dev: fixed the build; code review | meetings: standup\n2022-07-16,"
+        );
+    }
+
+    #[test]
+    fn test_entries_ron_round_trip() {
+        let entries = vec![TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 15, 10, 30, 0),
+            "notes with \"quotes\"",
+        )];
+        let imported = entries_from_ron(&entries_to_ron(&entries).unwrap()).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert!(imported[0].is_same_work(&entries[0]));
+        assert!(entries_from_ron("not entries").is_err());
+    }
+}
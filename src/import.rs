@@ -0,0 +1,318 @@
+// Reading entries from CSV files written by the app or by other time trackers
+
+use crate::TimeSheet::{parse_duration_minutes, TimeSheetEntry};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+const CSV_DATETIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+];
+const CSV_DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%m/%d/%Y"];
+
+/// Splits CSV text into rows of fields. Quoted fields can hold commas, doubled quotes
+/// and line breaks. Blank lines are left out.
+pub fn parse_csv(input: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|field| !field.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                }
+                row.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+    row.push(field);
+    if row.iter().any(|field| !field.is_empty()) {
+        rows.push(row);
+    }
+    rows
+}
+
+/// The column names and the rows after them. Single field rows before the column names,
+/// like the name and organization the app's own exports start with, are skipped.
+pub fn csv_header_and_rows(
+    mut rows: Vec<Vec<String>>,
+) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let header_index = rows
+        .iter()
+        .position(|row| row.len() > 1)
+        .ok_or_else(|| "The CSV doesn't have a row of column names".to_string())?;
+    let data_rows = rows.split_off(header_index + 1);
+    let header = rows.pop().unwrap_or_default();
+    Ok((header, data_rows))
+}
+
+/// Which CSV column, by index, holds each part of an entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CsvColumnMapping {
+    pub project: Option<usize>,
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+    pub date: Option<usize>,
+    pub duration: Option<usize>,
+    pub notes: Option<usize>,
+}
+
+impl CsvColumnMapping {
+    /// Picks columns by their names, so the app's own CSV exports map themselves
+    pub fn guess(header: &[String]) -> Self {
+        let find = |names: &[&str]| {
+            header.iter().position(|column| {
+                let column = column.trim().to_ascii_lowercase();
+                names.iter().any(|name| column == *name)
+            })
+        };
+        Self {
+            project: find(&["project_type", "project", "task"]),
+            start: find(&["work_start_datetime", "start", "started", "start time"]),
+            end: find(&["work_end_datetime", "end", "ended", "end time"]),
+            date: find(&["date", "day"]),
+            duration: find(&["elapsed_minutes", "duration", "minutes", "time"]),
+            notes: find(&["notes", "note", "description", "comment"]),
+        }
+    }
+}
+
+/// When a CSV field says something happened. Times without an offset are read at
+/// `utc_offset`. A date without a time is returned as a date.
+enum CsvWhen {
+    DateTime(DateTime<Utc>),
+    Date(NaiveDate),
+}
+
+fn parse_csv_when(value: &str, utc_offset: &FixedOffset) -> Option<CsvWhen> {
+    let value = value.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(CsvWhen::DateTime(datetime.with_timezone(&Utc)));
+    }
+    for format in CSV_DATETIME_FORMATS {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+            return utc_offset
+                .from_local_datetime(&datetime)
+                .single()
+                .map(|datetime| CsvWhen::DateTime(datetime.with_timezone(&Utc)));
+        }
+    }
+    CSV_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .map(CsvWhen::Date)
+}
+
+fn csv_entry(
+    row: &[String],
+    mapping: &CsvColumnMapping,
+    utc_offset: &FixedOffset,
+) -> Result<TimeSheetEntry, String> {
+    let field = |column: Option<usize>| {
+        column
+            .and_then(|column| row.get(column))
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    };
+    let when = |column: Option<usize>, name: &str| match field(column) {
+        Some(value) => parse_csv_when(value, utc_offset)
+            .map(Some)
+            .ok_or_else(|| format!("the {} \"{}\" isn't a date or time", name, value)),
+        None => Ok(None),
+    };
+
+    let project_type = field(mapping.project).ok_or("there's no project")?;
+    let notes = field(mapping.notes).unwrap_or_default();
+    let start = when(mapping.start, "start")?;
+    let end = when(mapping.end, "end")?;
+    let date = when(mapping.date, "date")?;
+    let minutes = field(mapping.duration)
+        .map(parse_duration_minutes)
+        .transpose()?;
+
+    match (start, end, minutes) {
+        (Some(CsvWhen::DateTime(start)), Some(CsvWhen::DateTime(end)), _) => {
+            if end < start {
+                return Err("it ends before it starts".to_string());
+            }
+            Ok(TimeSheetEntry::new(project_type, start, end, notes))
+        }
+        (Some(CsvWhen::DateTime(start)), None, Some(minutes)) => Ok(TimeSheetEntry::new(
+            project_type,
+            start,
+            start + Duration::seconds((minutes * 60.0).round() as i64),
+            notes,
+        )),
+        (start, _, Some(minutes)) => {
+            let date = match (date, start) {
+                (Some(CsvWhen::Date(date)), _) | (_, Some(CsvWhen::Date(date))) => date,
+                (Some(CsvWhen::DateTime(datetime)), _) => datetime.date_naive(),
+                _ => return Err("there's a duration but no date".to_string()),
+            };
+            if minutes <= 0.0 || minutes >= 24.0 * 60.0 {
+                return Err(format!(
+                    "{} minutes is more than a day, or no time at all",
+                    minutes
+                ));
+            }
+            Ok(TimeSheetEntry::from_minutes(
+                project_type,
+                minutes,
+                notes,
+                &date,
+                utc_offset,
+            ))
+        }
+        _ => Err("it needs a start and end time, or a duration".to_string()),
+    }
+}
+
+/// Builds an entry from each row using `mapping`. Rows that can't be read are skipped
+/// and described in the returned errors, numbered from the first row after the column
+/// names.
+pub fn entries_from_csv_rows(
+    rows: &[Vec<String>],
+    mapping: &CsvColumnMapping,
+    utc_offset: &FixedOffset,
+) -> (Vec<TimeSheetEntry>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        match csv_entry(row, mapping, utc_offset) {
+            Ok(entry) => entries.push(entry),
+            Err(error) => errors.push(format!("row {}: {}", index + 1, error)),
+        }
+    }
+    (entries, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{csv_with_header, entries_to_csv};
+    use crate::TimeSheet::datetime_from_ymd_hms;
+
+    #[test]
+    fn test_parse_csv_quoting() {
+        let rows = parse_csv("a,\"b, c\",\"say \"\"hi\"\"\"\r\n\n\"two\nlines\",,x");
+        assert_eq!(
+            rows,
+            vec![vec!["a", "b, c", "say \"hi\""], vec!["two\nlines", "", "x"],]
+        );
+    }
+
+    #[test]
+    fn test_csv_round_trip_skips_export_header() {
+        let entries = [TimeSheetEntry::new(
+            "dev",
+            datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 15, 10, 30, 0),
+            "fixed a bug, then\nreviewed",
+        )];
+        let entry_refs: Vec<&TimeSheetEntry> = entries.iter().collect();
+        let csv = csv_with_header(
+            &["Sam".to_string(), "Acme, Inc".to_string()],
+            entries_to_csv(&entry_refs),
+        );
+        let (header, rows) = csv_header_and_rows(parse_csv(&csv)).unwrap();
+        let mapping = CsvColumnMapping::guess(&header);
+        let (imported, errors) = entries_from_csv_rows(&rows, &mapping, &FixedOffset::east(0));
+        assert!(errors.is_empty());
+        assert_eq!(imported.len(), 1);
+        assert!(imported[0].is_same_work(&entries[0]));
+    }
+
+    #[test]
+    fn test_entries_from_mapped_columns() {
+        let csv =
+            "Notes,Day,Task,Hours\nstandup,07/15/2022,Meetings,0:15\nbuild,2022-07-16,dev,1h30m";
+        let (header, rows) = csv_header_and_rows(parse_csv(csv)).unwrap();
+        let mapping = CsvColumnMapping {
+            project: Some(2),
+            date: Some(1),
+            duration: Some(3),
+            notes: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(header.len(), 4);
+        let (imported, errors) = entries_from_csv_rows(&rows, &mapping, &FixedOffset::east(0));
+        assert!(errors.is_empty());
+        assert_eq!(imported[0].project_type, "Meetings");
+        assert_eq!(imported[0].notes, "standup");
+        assert_eq!(
+            imported[0].work_start_datetime,
+            datetime_from_ymd_hms(2022, 7, 15, 0, 0, 0)
+        );
+        assert_eq!(
+            imported[1].work_end_datetime - imported[1].work_start_datetime,
+            Duration::minutes(90)
+        );
+    }
+
+    #[test]
+    fn test_entries_from_local_times_and_durations() {
+        let rows = parse_csv("dev,2022-07-15 09:00,,45\ndev,2022-07-15 09:00,2022-07-15 10:00,");
+        let mapping = CsvColumnMapping {
+            project: Some(0),
+            start: Some(1),
+            end: Some(2),
+            duration: Some(3),
+            ..Default::default()
+        };
+        let eastern = FixedOffset::west(5 * 3600);
+        let (imported, errors) = entries_from_csv_rows(&rows, &mapping, &eastern);
+        assert!(errors.is_empty());
+        assert_eq!(
+            imported[0].work_start_datetime,
+            datetime_from_ymd_hms(2022, 7, 15, 14, 0, 0)
+        );
+        assert_eq!(
+            imported[0].work_end_datetime,
+            datetime_from_ymd_hms(2022, 7, 15, 14, 45, 0)
+        );
+        assert_eq!(
+            imported[1].work_end_datetime,
+            datetime_from_ymd_hms(2022, 7, 15, 15, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_bad_rows_are_reported_not_fatal() {
+        let rows = parse_csv(
+            ",2022-07-15,30\ndev,someday,30\ndev,2022-07-15,lots\ndev,2022-07-15,30\nSubtotal,,",
+        );
+        let mapping = CsvColumnMapping {
+            project: Some(0),
+            date: Some(1),
+            duration: Some(2),
+            ..Default::default()
+        };
+        let (imported, errors) = entries_from_csv_rows(&rows, &mapping, &FixedOffset::east(0));
+        assert_eq!(imported.len(), 1);
+        assert_eq!(errors.len(), 4);
+        assert!(errors[0].starts_with("row 1:"));
+        assert!(errors[3].starts_with("row 5:"));
+    }
+}
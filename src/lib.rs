@@ -3,6 +3,16 @@
 mod app;
 pub use app::TemplateApp;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod backup;
+
+mod export;
+
+mod import;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod pdf;
+
 #[allow(non_snake_case)]
 mod TimeSheet;
 pub use TimeSheet::{TimeSheetEntry, TimeSheetSummary};
@@ -0,0 +1,282 @@
+// A minimal PDF writer for the printable timesheet, so no PDF library is needed
+
+use crate::export::{rounded_summary_rows, ReportLabels};
+use crate::TimeSheet::{format_duration_hours, RoundingSettings, TimeSheetSummary};
+use chrono::Duration;
+
+// US letter, landscape, in PDF points
+const PDF_PAGE_WIDTH: f64 = 792.0;
+const PDF_PAGE_HEIGHT: f64 = 612.0;
+const PDF_MARGIN: f64 = 36.0;
+// Room kept free under the table for the signature lines
+const PDF_SIGNATURE_HEIGHT: f64 = 60.0;
+
+/// Escapes text for a PDF string, anything the standard fonts can't show becomes '?'
+fn pdf_text(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// Drawing commands for each page of a PDF using only the Helvetica font. Positions
+/// are in points from the bottom left corner of the page.
+struct PdfPages {
+    pages: Vec<String>,
+}
+
+impl PdfPages {
+    fn new() -> Self {
+        Self {
+            pages: vec![String::new()],
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(String::new());
+    }
+
+    fn current(&mut self) -> &mut String {
+        self.pages.last_mut().unwrap()
+    }
+
+    fn text(&mut self, x: f64, y: f64, size: f64, value: &str) {
+        let command = format!(
+            "BT /F1 {:.1} Tf {:.1} {:.1} Td ({}) Tj ET\n",
+            size,
+            x,
+            y,
+            pdf_text(value)
+        );
+        self.current().push_str(&command);
+    }
+
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        let command = format!("{:.1} {:.1} m {:.1} {:.1} l S\n", x1, y1, x2, y2);
+        self.current().push_str(&command);
+    }
+
+    /// The finished file. Everything written is ASCII, so it fits in a String.
+    fn finish(self) -> String {
+        let page_count = self.pages.len();
+        // 1 is the catalog, 2 the page tree, 3 the font, then a page and its contents
+        // for each page
+        let kids: Vec<String> = (0..page_count)
+            .map(|index| format!("{} 0 R", 4 + 2 * index))
+            .collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                page_count
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        for (index, contents) in self.pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                PDF_PAGE_WIDTH,
+                PDF_PAGE_HEIGHT,
+                5 + 2 * index
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                contents.len(),
+                contents
+            ));
+        }
+
+        let mut pdf = "%PDF-1.4\n".to_string();
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", index + 1, object));
+        }
+        let xref_offset = pdf.len();
+        pdf.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            pdf.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        pdf.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ));
+        pdf
+    }
+}
+
+/// Cuts `value` short enough to fit in `width` points of Helvetica at `size`
+fn pdf_fit(value: &str, width: f64, size: f64) -> String {
+    // Helvetica averages a little over half the font size per character
+    let max_chars = (width / (size * 0.55)).floor().max(1.0) as usize;
+    if value.chars().count() <= max_chars {
+        value.to_string()
+    } else {
+        let mut fitted: String = value.chars().take(max_chars.saturating_sub(3)).collect();
+        fitted.push_str("...");
+        fitted
+    }
+}
+
+/// The project by date summary matrix as a printable PDF, laid out like the summary
+/// grid with each cell rounded the same way, and lines to sign and date at the end
+pub fn summary_to_pdf(
+    summary: &TimeSheetSummary,
+    rounding: &RoundingSettings,
+    labels: &ReportLabels,
+    header: &[String],
+) -> String {
+    let (mut rows, day_totals) = rounded_summary_rows(summary, rounding);
+    rows.push(("total".to_string(), day_totals));
+
+    let left = PDF_MARGIN;
+    let right = PDF_PAGE_WIDTH - PDF_MARGIN;
+    let top = PDF_PAGE_HEIGHT - PDF_MARGIN;
+    let project_width = 120.0;
+    let column_width = (right - left - project_width) / (summary.period_dates.len() + 1) as f64;
+    let size = (column_width / 4.0).min(9.0);
+    let row_height = size * 2.0;
+    let column_x = |column: usize| left + project_width + column as f64 * column_width + 2.0;
+
+    let mut pages = PdfPages::new();
+    let mut y = top - 16.0;
+    let title = if labels.title.is_empty() {
+        "Timesheet"
+    } else {
+        &labels.title
+    };
+    pages.text(left, y, 16.0, title);
+    // The header already holds the name from the labels
+    for line in header {
+        y -= 18.0;
+        pages.text(left, y, 11.0, line);
+    }
+    if let (Some(first), Some(last)) = (summary.period_dates.first(), summary.period_dates.last()) {
+        y -= 16.0;
+        pages.text(
+            left,
+            y,
+            11.0,
+            &format!("{} to {}", first.format("%F"), last.format("%F")),
+        );
+    }
+    y -= 12.0;
+
+    // The header is repeated at the top of each page the table runs onto
+    let header = |pages: &mut PdfPages, y: f64| -> f64 {
+        let y = y - row_height;
+        pages.text(left + 2.0, y, size, "project");
+        for (column, date) in summary.period_dates.iter().enumerate() {
+            pages.text(
+                column_x(column),
+                y + size,
+                size,
+                &date.format("%m/%d").to_string(),
+            );
+            pages.text(column_x(column), y, size, &date.format("%a").to_string());
+        }
+        pages.text(column_x(summary.period_dates.len()), y, size, "total");
+        pages.line(left, y - size * 0.5, right, y - size * 0.5);
+        y - size * 0.5
+    };
+    y = header(&mut pages, y);
+    let last_row = rows.len() - 1;
+    for (index, (project, cells)) in rows.iter().enumerate() {
+        if y - row_height < PDF_MARGIN + PDF_SIGNATURE_HEIGHT {
+            pages.new_page();
+            y = header(&mut pages, top);
+        }
+        if index == last_row {
+            pages.line(left, y, right, y);
+        }
+        y -= row_height;
+        pages.text(
+            left + 2.0,
+            y + size * 0.5,
+            size,
+            &pdf_fit(project, project_width - 4.0, size),
+        );
+        for (column, worked) in cells.iter().enumerate() {
+            pages.text(
+                column_x(column),
+                y + size * 0.5,
+                size,
+                &format_duration_hours(worked),
+            );
+        }
+        let total = cells
+            .iter()
+            .fold(Duration::zero(), |total, worked| total + *worked);
+        pages.text(
+            column_x(cells.len()),
+            y + size * 0.5,
+            size,
+            &format_duration_hours(&total),
+        );
+    }
+
+    y -= PDF_SIGNATURE_HEIGHT * 0.6;
+    pages.line(left, y, left + 250.0, y);
+    pages.text(left, y - 12.0, 9.0, "Signature");
+    pages.line(left + 300.0, y, left + 450.0, y);
+    pages.text(left + 300.0, y - 12.0, 9.0, "Date");
+    if !labels.footer.is_empty() {
+        pages.text(left, PDF_MARGIN - 12.0, 9.0, &labels.footer);
+    }
+    pages.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::export_header;
+    use crate::TimeSheet::datetime_from_ymd_hms;
+    use crate::TimeSheet::TimeSheetEntry;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_summary_to_pdf() {
+        let entries = [TimeSheetEntry::new(
+            "dev (backend)",
+            datetime_from_ymd_hms(2022, 7, 15, 9, 0, 0),
+            datetime_from_ymd_hms(2022, 7, 15, 10, 30, 0),
+            "",
+        )];
+        let start_date = NaiveDate::from_ymd(2022, 7, 15);
+        let end_date = NaiveDate::from_ymd(2022, 7, 16);
+        let summary = TimeSheetSummary::new(&entries, &start_date, &end_date);
+        let labels = ReportLabels {
+            title: "Timesheet".to_string(),
+            name: "Sam".to_string(),
+            footer: String::new(),
+        };
+        let header = export_header(&labels.name, "Acme");
+        let pdf = summary_to_pdf(&summary, &RoundingSettings::default(), &labels, &header);
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(dev \\(backend\\)) Tj"));
+        assert!(pdf.contains("(1.50) Tj"));
+        assert!(pdf.contains("(Sam) Tj"));
+        assert!(pdf.contains("(Acme) Tj"));
+        assert!(pdf.contains("(2022-07-15 to 2022-07-16) Tj"));
+        assert!(pdf.contains("(Signature) Tj"));
+
+        // Every object offset in the cross reference table points at that object
+        let xref = pdf.rfind("xref\n").unwrap();
+        for (index, line) in pdf[xref..].lines().skip(3).take(5).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", index + 1)));
+        }
+    }
+}